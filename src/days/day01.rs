@@ -1,13 +1,14 @@
 use crate::{Solution, SolutionPair};
 
-const START_POSITION: i32 = 50;
+const DIAL_SIZE: i64 = 100;
+const START_POSITION: i64 = 50;
 
-fn prepare(input: &str) -> Vec<i32> {
+fn prepare(input: &str) -> Vec<i64> {
     input
         .split_whitespace()
         .filter(|line| !line.is_empty())
         .map(|line| {
-            let v: i32 = line[1..].parse().unwrap();
+            let v: i64 = line[1..].parse().unwrap();
             match &line[0..1] {
                 "L" => -v,
                 "R" => v,
@@ -17,31 +18,92 @@ fn prepare(input: &str) -> Vec<i32> {
         .collect()
 }
 
+/// A circular dial numbered from `0` to `size - 1`, turning right increases the position.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Dial {
+    size: i64,
+    position: i64,
+}
+
+/// One rotation of the dial: `clicks` clicks from position `from` to position `to`, rightwards
+/// when `clicks` is positive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Rotation {
+    size: i64,
+    from: i64,
+    to: i64,
+    clicks: i64,
+}
+
+impl Dial {
+    fn new(size: i64, start: i64) -> Self {
+        assert!((0..size).contains(&start), "start must be on the dial");
+        Dial {
+            size,
+            position: start,
+        }
+    }
+
+    fn rotate(&mut self, clicks: i64) -> Rotation {
+        let from = self.position;
+        self.position = (from + clicks).rem_euclid(self.size);
+        Rotation {
+            size: self.size,
+            from,
+            to: self.position,
+            clicks,
+        }
+    }
+
+    /// Apply the rotations in order and return them.
+    fn run(&mut self, rotations: &[i64]) -> Vec<Rotation> {
+        rotations
+            .iter()
+            .map(|clicks| self.rotate(*clicks))
+            .collect()
+    }
+}
+
+impl Rotation {
+    /// Return the number of complete turns of the dial.
+    fn full_turns(&self) -> u64 {
+        self.clicks.unsigned_abs() / self.size.unsigned_abs()
+    }
+
+    /// Return the number of clicks that land on `position`, the starting position excluded.
+    fn times_passed(&self, position: i64) -> u64 {
+        let distance = if self.clicks >= 0 {
+            position - self.from
+        } else {
+            self.from - position
+        };
+        // number of clicks before reaching the position for the first time.
+        let first = match distance.rem_euclid(self.size) {
+            0 => self.size,
+            d => d,
+        };
+        // each full turn passes the position once, then the remaining clicks may reach it.
+        let remaining = self.clicks.abs() % self.size;
+        self.full_turns() + u64::from(first <= remaining)
+    }
+}
+
 fn solve_part1(input: &str) -> u64 {
-    let rotations = prepare(input);
-    rotations
+    // count the rotations ending on 0
+    Dial::new(DIAL_SIZE, START_POSITION)
+        .run(&prepare(input))
         .iter()
-        .fold((START_POSITION, 0u64), |(pos, zeroes), rot| {
-            let new_pos = (pos + rot) % 100;
-            (new_pos, zeroes + if new_pos == 0 { 1 } else { 0 })
-        })
-        .1
+        .filter(|rotation| rotation.to == 0)
+        .count() as u64
 }
 
 fn solve_part2(input: &str) -> u64 {
-    let rotations = prepare(input);
-    rotations
+    // count the clicks landing on 0
+    Dial::new(DIAL_SIZE, START_POSITION)
+        .run(&prepare(input))
         .iter()
-        .fold((START_POSITION, 0u64), |(pos, zeroes), rot| {
-            let new_pos = (pos + rot).rem_euclid(100);
-
-            let full_turns: u64 = (rot.abs() / 100).try_into().unwrap();
-            let resets: u64 = (new_pos == 0
-                || (pos != 0 && ((*rot > 0 && new_pos < pos) || (*rot < 0 && new_pos > pos))))
-                .into();
-            (new_pos, zeroes + full_turns + resets)
-        })
-        .1
+        .map(|rotation| rotation.times_passed(0))
+        .sum()
 }
 
 pub fn solve(input: String) -> SolutionPair {
@@ -53,6 +115,7 @@ pub fn solve(input: String) -> SolutionPair {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::etc::random::XorShift;
 
     const EXAMPLE_INPUT: &str = "
     L68
@@ -83,4 +146,50 @@ mod tests {
     fn example_part2() {
         assert_eq!(solve_part2(EXAMPLE_INPUT), 6);
     }
+
+    #[test]
+    fn rotations() {
+        let mut dial = Dial::new(DIAL_SIZE, START_POSITION);
+        let rotation = dial.rotate(1000);
+        assert_eq!((rotation.to, rotation.full_turns()), (50, 10));
+        assert_eq!(rotation.times_passed(0), 10);
+        assert_eq!(rotation.times_passed(50), 10);
+        let rotation = dial.rotate(-50);
+        assert_eq!((rotation.to, rotation.full_turns()), (0, 0));
+        assert_eq!(rotation.times_passed(0), 1);
+        let rotation = dial.rotate(-5);
+        assert_eq!(rotation.to, 95);
+        assert_eq!(rotation.times_passed(0), 0);
+    }
+
+    #[test]
+    fn matches_click_by_click() {
+        let mut rng = XorShift::new(1);
+        for _ in 0..200 {
+            let size = rng.range(1..=20);
+            let start = rng.range(0..=size - 1);
+            let rotations = (0..10).map(|_| rng.range(-50..=50)).collect::<Vec<_>>();
+
+            let mut position = start;
+            for rotation in Dial::new(size, start).run(&rotations) {
+                let mut passed = vec![0; size as usize];
+                for _ in 0..rotation.clicks.abs() {
+                    position = (position + rotation.clicks.signum()).rem_euclid(size);
+                    passed[position as usize] += 1;
+                }
+                assert_eq!(rotation.to, position);
+                assert_eq!(
+                    rotation.full_turns(),
+                    rotation.clicks.unsigned_abs() / size as u64
+                );
+                for p in 0..size {
+                    assert_eq!(
+                        rotation.times_passed(p),
+                        passed[p as usize],
+                        "{rotation:?} {p}"
+                    );
+                }
+            }
+        }
+    }
 }
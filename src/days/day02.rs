@@ -1,3 +1,4 @@
+use crate::etc::ranges::parse_range;
 use crate::{Solution, SolutionPair};
 
 use std::ops::RangeInclusive;
@@ -6,75 +7,109 @@ fn prepare(input: &str) -> Vec<RangeInclusive<u64>> {
     input
         .trim()
         .split(',')
-        .map(|txt| {
-            let pair = txt.split_once('-').unwrap();
-            RangeInclusive::new(pair.0.parse().unwrap(), pair.1.parse().unwrap())
-        })
+        .map(|txt| parse_range(txt).unwrap())
         .collect()
 }
 
-fn pattern_repeats_twice(n: u64) -> bool {
-    if n == 0 {
-        return false;
-    }
-    let len = n.ilog10() + 1;
-    let half_len = len / 2;
-    return (len % 2 == 0) && n.rem_euclid(1 + 10u64.pow(half_len)) == 0;
+/// Return the number of decimal digits of `n`.
+fn digits(n: u64) -> u32 {
+    n.checked_ilog10().map_or(1, |log| log + 1)
 }
 
-fn solve_part1(input: &str) -> u64 {
-    prepare(input)
-        .iter()
-        .map(|range| {
-            range
-                .clone()
-                .filter(|n| pattern_repeats_twice(*n))
-                .sum::<u64>()
-        })
-        .sum::<u64>()
+/// Return `1 + 10^m + 10^2m + ... + 10^(k-1)m`: multiplying an `m`-digit block by this factor
+/// repeats the block `k` times.
+fn repeat_factor(m: u32, k: u32) -> u128 {
+    (0..k).fold(0, |factor, _| 1 + factor * 10u128.pow(m))
 }
 
-fn pattern_repeats_k(k: u64, n: u64) -> bool {
-    // `n` must be divisible by decimal pattern `Z1...Z1` where `Z` is `0` repeated `m-1` times
-    // and `Z1` is repeated `k` times
-    if k == 0 || n == 0 {
-        return false;
+/// Return the sum of the numbers in the range made of an `m`-digit block repeated `k` times.
+///
+/// These numbers are `block * repeat_factor(m, k)` for the blocks from `10^(m-1)` to `10^m - 1`,
+/// the blocks giving numbers in the range are consecutive so their sum is arithmetic.
+fn sum_of_repeats(range: &RangeInclusive<u64>, m: u32, k: u32) -> u128 {
+    let factor = repeat_factor(m, k);
+    let first = 10u128
+        .pow(m - 1)
+        .max(u128::from(*range.start()).div_ceil(factor));
+    let last = (10u128.pow(m) - 1).min(u128::from(*range.end()) / factor);
+    if first > last {
+        return 0;
     }
-    let len: u64 = (n.ilog10() + 1).into();
-    if len.rem_euclid(k) != 0 {
-        return false;
+    factor * (first + last) * (last - first + 1) / 2
+}
+
+/// Return the distinct prime factors of `n`.
+fn prime_factors(mut n: u32) -> Vec<u32> {
+    let mut factors = vec![];
+    let mut p = 2;
+    while p * p <= n {
+        if n.is_multiple_of(p) {
+            factors.push(p);
+            while n.is_multiple_of(p) {
+                n /= p;
+            }
+        }
+        p += 1;
     }
-    let m: u32 = (len / k).try_into().unwrap();
-    let mut p = 0;
-    for _ in 0..k {
-        p = 1 + p * 10u64.pow(m);
+    if n > 1 {
+        factors.push(n);
     }
-    return n.rem_euclid(p) == 0;
+    factors
 }
 
-fn pattern_repeats_at_least_twice(n: u64) -> bool {
-    if n == 0 {
-        return false;
-    }
-    let len: u64 = (n.ilog10() + 1).into();
-    for k in 2..=len {
-        if pattern_repeats_k(k, n) {
-            return true;
+/// Return the lengths of the numbers in the range.
+fn lengths(range: &RangeInclusive<u64>) -> RangeInclusive<u32> {
+    digits(*range.start())..=digits(*range.end())
+}
+
+fn solve_part1(input: &str) -> u64 {
+    let sum: u128 = prepare(input)
+        .iter()
+        .map(|range| {
+            lengths(range)
+                .filter(|len| len.is_multiple_of(2))
+                .map(|len| sum_of_repeats(range, len / 2, 2))
+                .sum::<u128>()
+        })
+        .sum();
+    u64::try_from(sum).unwrap()
+}
+
+/// Return the sum of the numbers in the range, of length `len`, made of a block repeated at least
+/// twice.
+///
+/// A block repeated `k` times is also a block repeated `p` times for any prime `p` dividing `k`,
+/// so these numbers are the union over the primes `p` dividing `len` of the blocks repeated `p`
+/// times. Being a block repeated `p1` times and a block repeated `p2` times is being a block
+/// repeated `p1 * p2` times, hence the inclusion–exclusion over the sets of primes.
+fn sum_of_any_repeats(range: &RangeInclusive<u64>, len: u32) -> u128 {
+    let primes = prime_factors(len);
+    let (mut added, mut removed) = (0, 0);
+    for subset in 1..(1u32 << primes.len()) {
+        let k: u32 = (0..primes.len())
+            .filter(|i| subset & (1 << i) != 0)
+            .map(|i| primes[i])
+            .product();
+        let sum = sum_of_repeats(range, len / k, k);
+        if subset.count_ones() % 2 == 1 {
+            added += sum;
+        } else {
+            removed += sum;
         }
     }
-    return false;
+    added - removed
 }
 
 fn solve_part2(input: &str) -> u64 {
-    prepare(input)
+    let sum: u128 = prepare(input)
         .iter()
         .map(|range| {
-            range
-                .clone()
-                .filter(|n| pattern_repeats_at_least_twice(*n))
-                .sum::<u64>()
+            lengths(range)
+                .map(|len| sum_of_any_repeats(range, len))
+                .sum::<u128>()
         })
-        .sum::<u64>()
+        .sum();
+    u64::try_from(sum).unwrap()
 }
 
 pub fn solve(input: String) -> SolutionPair {
@@ -87,6 +122,48 @@ pub fn solve(input: String) -> SolutionPair {
 mod tests {
     use super::*;
 
+    // Scanning predicates, kept as the reference for the closed-form sums.
+
+    fn pattern_repeats_twice(n: u64) -> bool {
+        if n == 0 {
+            return false;
+        }
+        let len = n.ilog10() + 1;
+        let half_len = len / 2;
+        return (len % 2 == 0) && n.rem_euclid(1 + 10u64.pow(half_len)) == 0;
+    }
+
+    fn pattern_repeats_k(k: u64, n: u64) -> bool {
+        // `n` must be divisible by decimal pattern `Z1...Z1` where `Z` is `0` repeated `m-1` times
+        // and `Z1` is repeated `k` times
+        if k == 0 || n == 0 {
+            return false;
+        }
+        let len: u64 = (n.ilog10() + 1).into();
+        if len.rem_euclid(k) != 0 {
+            return false;
+        }
+        let m: u32 = (len / k).try_into().unwrap();
+        let mut p = 0;
+        for _ in 0..k {
+            p = 1 + p * 10u64.pow(m);
+        }
+        return n.rem_euclid(p) == 0;
+    }
+
+    fn pattern_repeats_at_least_twice(n: u64) -> bool {
+        if n == 0 {
+            return false;
+        }
+        let len: u64 = (n.ilog10() + 1).into();
+        for k in 2..=len {
+            if pattern_repeats_k(k, n) {
+                return true;
+            }
+        }
+        return false;
+    }
+
     const EXAMPLE_INPUT: &str = "11-22,95-115,998-1012,1188511880-1188511890,222220-222224,1698522-1698528,446443-446449,38593856-38593862,565653-565659,824824821-824824827,2121212118-2121212124";
 
     #[test]
@@ -151,4 +228,41 @@ mod tests {
     fn example_part2() {
         assert_eq!(solve_part2(EXAMPLE_INPUT), 4174379265u64);
     }
+
+    #[test]
+    fn closed_form_matches_scanning() {
+        let ranges = [
+            1u64..=1,
+            1..=99_999,
+            5..=1_234_567,
+            998_000..=1_001_500,
+            100_000..=999_999,
+            12_341_234..=12_351_235,
+        ];
+        for range in ranges {
+            let twice: u64 = range.clone().filter(|n| pattern_repeats_twice(*n)).sum();
+            let any: u64 = range
+                .clone()
+                .filter(|n| pattern_repeats_at_least_twice(*n))
+                .sum();
+            let input = format!("{}-{}", range.start(), range.end());
+            assert_eq!(solve_part1(&input), twice, "{input}");
+            assert_eq!(solve_part2(&input), any, "{input}");
+        }
+    }
+
+    #[test]
+    fn inclusion_exclusion() {
+        assert_eq!(prime_factors(12), vec![2, 3]);
+        assert_eq!(prime_factors(7), vec![7]);
+        assert_eq!(prime_factors(1), vec![]);
+        // 6 digits: blocks of 3 repeated twice, of 2 repeated 3 times, minus the 6 repdigits
+        // counted twice.
+        let range = 100_000..=999_999;
+        let repdigits: u128 = (1..=9).map(|d| d * 111_111).sum();
+        assert_eq!(
+            sum_of_any_repeats(&range, 6),
+            sum_of_repeats(&range, 3, 2) + sum_of_repeats(&range, 2, 3) - repdigits
+        );
+    }
 }
@@ -14,42 +14,49 @@ fn prepare(input: &str) -> Vec<Vec<u32>> {
         .collect()
 }
 
-fn solve_part1(input: &str) -> u64 {
-    // Find `a` the battery with greatest joltage of the bank that is not the last battery,
-    // and `b` the battery after `a` with the greatest joltage in the bank.
-    //
-    // Be `j(a)` and `j(b)` the joltage of each battery, the largest voltage you can produce
-    // is `10*j(a) + j(b)`.
-    let banks = prepare(input);
-    banks
-        .into_iter()
-        .map(|bank| -> u64 {
-            let slice = &bank[0..bank.len() - 1];
-            let j_a = *slice.iter().max().unwrap();
-            let a = slice.iter().position(|jolt| *jolt == j_a).unwrap();
-            let slice = &bank[a + 1..bank.len()];
-            let j_b = *slice.iter().max().unwrap();
-            (10 * j_a + j_b).into()
-        })
+/// Choose `k` of the digits, keeping their order, to write the largest number in the given base.
+/// Return that number and the indices of the chosen digits.
+///
+/// The chosen digits are kept on a stack: a digit replaces the smaller digits on top of the stack
+/// as long as enough digits remain after it to choose `k` digits in total.
+fn largest_subsequence(digits: &[u32], k: usize, base: u32) -> (u64, Vec<usize>) {
+    assert!(k <= digits.len(), "not enough digits");
+    assert!(
+        digits.iter().all(|d| *d < base),
+        "digits must be lower than the base"
+    );
+    let mut chosen: Vec<usize> = Vec::with_capacity(k);
+    for (i, digit) in digits.iter().enumerate() {
+        let remaining = digits.len() - i;
+        while chosen.last().is_some_and(|top| digits[*top] < *digit)
+            && chosen.len() - 1 + remaining >= k
+        {
+            chosen.pop();
+        }
+        if chosen.len() < k {
+            chosen.push(i);
+        }
+    }
+    let value = chosen.iter().fold(0, |value, i| {
+        value * u64::from(base) + u64::from(digits[*i])
+    });
+    (value, chosen)
+}
+
+/// Return the total joltage when turning on `k` batteries in each bank.
+fn total_joltage(input: &str, k: usize) -> u64 {
+    prepare(input)
+        .iter()
+        .map(|bank| largest_subsequence(bank, k, 10).0)
         .sum()
 }
 
+fn solve_part1(input: &str) -> u64 {
+    total_joltage(input, 2)
+}
+
 fn solve_part2(input: &str) -> u64 {
-    let banks = prepare(input);
-    banks
-        .into_iter()
-        .map(|bank| {
-            let mut highest = 0;
-            let mut start = 0;
-            for d in (0usize..12).rev() {
-                let slice = &bank[start..bank.len() - d];
-                let j_i = *slice.iter().max().unwrap();
-                start += slice.iter().position(|jolt| *jolt == j_i).unwrap() + 1;
-                highest = 10 * highest + <u32 as Into<u64>>::into(j_i);
-            }
-            highest
-        })
-        .sum()
+    total_joltage(input, 12)
 }
 
 pub fn solve(input: String) -> SolutionPair {
@@ -61,6 +68,7 @@ pub fn solve(input: String) -> SolutionPair {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::etc::random::XorShift;
 
     const EXAMPLE_INPUT: &str = "
     987654321111111
@@ -77,4 +85,46 @@ mod tests {
     fn example_part2() {
         assert_eq!(solve_part2(EXAMPLE_INPUT), 3121910778619);
     }
+
+    #[test]
+    fn subsequence() {
+        assert_eq!(
+            largest_subsequence(&[8, 1, 8, 1, 8, 1, 9, 1], 3, 10),
+            (891, vec![0, 6, 7])
+        );
+        assert_eq!(
+            largest_subsequence(&[8, 1, 8, 1, 8, 1, 9, 1], 2, 10),
+            (91, vec![6, 7])
+        );
+        assert_eq!(largest_subsequence(&[3, 3, 3], 2, 10), (33, vec![0, 1]));
+        assert_eq!(largest_subsequence(&[3, 3, 3], 0, 10), (0, vec![]));
+        assert_eq!(
+            largest_subsequence(&[1, 15, 0, 10], 2, 16),
+            (0xfa, vec![1, 3])
+        );
+    }
+
+    #[test]
+    fn subsequence_matches_brute_force() {
+        let mut rng = XorShift::new(3);
+        for _ in 0..300 {
+            let len = rng.range(1..=10) as usize;
+            let digits = (0..len)
+                .map(|_| rng.range(0..=3) as u32)
+                .collect::<Vec<_>>();
+            let k = rng.range(0..=len as i64) as usize;
+            let best = (0u32..1 << len)
+                .filter(|mask| mask.count_ones() as usize == k)
+                .map(|mask| {
+                    (0..len)
+                        .filter(|i| mask & (1 << i) != 0)
+                        .fold(0, |value, i| value * 4 + u64::from(digits[i]))
+                })
+                .max()
+                .unwrap();
+            let (value, indices) = largest_subsequence(&digits, k, 4);
+            assert_eq!(value, best, "{digits:?} {k}");
+            assert!(indices.windows(2).all(|w| w[0] < w[1]));
+        }
+    }
 }
@@ -1,4 +1,4 @@
-use crate::{Grid, Point};
+use crate::Grid;
 use crate::{Solution, SolutionPair};
 
 #[derive(Copy, Clone, Debug)]
@@ -17,52 +17,64 @@ fn prepare(input: &str) -> Map {
     })
 }
 
-fn is_accessible_roll(map: &Map, pos: &Point) -> bool {
-    if matches!(map.unchecked_get(pos), Cell::Free) {
-        return false;
-    }
+/// A roll is accessible when fewer than this many rolls surround it.
+const CROWDED: u8 = 4;
 
-    let mut adjacent_rolls = 0;
-    map.for_each_tchebychev_neighbour(pos, |_, neighbour| {
-        if matches!(neighbour, Cell::Roll) {
-            adjacent_rolls += 1;
+/// Remove the accessible rolls round after round, all at once in each round, until no roll is
+/// accessible. Return the round, starting at 1, in which each roll is removed; `None` for the
+/// free cells and the rolls that are never removed.
+///
+/// The number of neighbouring rolls is computed once, then each removal decrements the count of
+/// its neighbours: only those whose count drops below `CROWDED` are scheduled for the next round.
+fn removal_rounds(map: &Map) -> Grid<Option<u32>> {
+    let is_roll = |cell: &Cell| matches!(cell, Cell::Roll);
+    let mut counts: Grid<u8> = Grid::default(map.lines, map.columns);
+    let mut rounds: Grid<Option<u32>> = Grid::default(map.lines, map.columns);
+    let mut removed = vec![];
+    map.for_each_with_position(|pos, cell| {
+        if is_roll(cell) {
+            let mut count = 0;
+            map.for_each_tchebychev_neighbour(&pos, |_, neighbour| {
+                count += u8::from(is_roll(neighbour));
+            });
+            counts.update(&pos, count);
+            if count < CROWDED {
+                rounds.update(&pos, Some(1));
+                removed.push(pos);
+            }
         }
     });
 
-    adjacent_rolls < 4
+    let mut round = 1;
+    while !removed.is_empty() {
+        let mut next = vec![];
+        for pos in &removed {
+            map.for_each_tchebychev_neighbour(pos, |neighbour, cell| {
+                if is_roll(cell) && rounds.strict_get(&neighbour).is_none() {
+                    let count = counts.get_mut(&neighbour).unwrap();
+                    *count -= 1;
+                    if *count == CROWDED - 1 {
+                        rounds.update(&neighbour, Some(round + 1));
+                        next.push(neighbour);
+                    }
+                }
+            });
+        }
+        removed = next;
+        round += 1;
+    }
+    rounds
 }
 
-fn solve_part1(input: &str) -> u64 {
-    let map = prepare(input);
-    let mut accessible_rolls = 0u64;
-    map.for_each_with_position(|origin, _| {
-        if is_accessible_roll(&map, &origin) {
-            accessible_rolls += 1;
-        }
-    });
-    accessible_rolls
+fn solve_part1(input: &str) -> usize {
+    // the rolls accessible at first are the ones removed in the first round
+    let rounds = removal_rounds(&prepare(input));
+    rounds.iter().filter(|round| **round == Some(1)).count()
 }
 
 fn solve_part2(input: &str) -> usize {
-    let mut map = prepare(input);
-    let rolls_before = map.iter().filter(|cell| matches!(cell, Cell::Roll)).count();
-    loop {
-        let mut removable: Vec<Point> = Default::default();
-        map.for_each_with_position(|pos, _| {
-            if is_accessible_roll(&map, &pos) {
-                removable.push(pos);
-            }
-        });
-        if removable.is_empty() {
-            break;
-        }
-        for pos in &removable {
-            *map.get_mut(pos).unwrap() = Cell::Free;
-        }
-    }
-
-    let rolls_after = map.iter().filter(|cell| matches!(cell, Cell::Roll)).count();
-    rolls_before - rolls_after
+    let rounds = removal_rounds(&prepare(input));
+    rounds.iter().filter(|round| round.is_some()).count()
 }
 
 pub fn solve(input: String) -> SolutionPair {
@@ -96,4 +108,34 @@ mod tests {
     fn example_part2() {
         assert_eq!(solve_part2(EXAMPLE_INPUT), 43);
     }
+
+    #[test]
+    fn rounds() {
+        let rounds = removal_rounds(&prepare(EXAMPLE_INPUT));
+        let per_round = (1..=10)
+            .map(|r| rounds.iter().filter(|round| **round == Some(r)).count())
+            .collect::<Vec<_>>();
+        assert_eq!(per_round, vec![13, 12, 7, 5, 2, 1, 1, 1, 1, 0]);
+
+        let rounds = removal_rounds(&prepare(
+            "
+            @@@
+            @@@
+            @@.",
+        ));
+        assert_eq!(
+            rounds.items,
+            vec![
+                Some(1),
+                Some(2),
+                Some(1),
+                Some(2),
+                Some(3),
+                Some(2),
+                Some(1),
+                Some(2),
+                None
+            ]
+        );
+    }
 }
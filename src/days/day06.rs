@@ -1,114 +1,170 @@
+use crate::etc::expr::{BinOp, Expr};
 use crate::{Solution, SolutionPair};
-
-#[derive(Copy, Clone, Debug, PartialEq, Eq)]
-enum Op {
-    Add,
-    Mul,
-}
+use std::ops::Range;
 
 #[derive(Clone, Debug, PartialEq, Eq)]
 struct Problem {
-    operation: Op,
+    operation: BinOp,
     operands: Vec<u64>,
 }
 
 impl Problem {
-    fn eval(&self) -> u64 {
-        match self.operation {
-            Op::Add => self.operands.iter().copied().fold(0, |a, b| a + b),
-            Op::Mul => self.operands.iter().copied().fold(1, |a, b| a * b),
-        }
+    /// Return the expression applying the operation to the operands from left to right, `None`
+    /// if there are no operands.
+    fn expr(&self) -> Option<Expr<u64>> {
+        Expr::chain(self.operation, self.operands.iter().copied())
     }
-}
-
-fn read_operations(line: &str) -> Vec<Op> {
-    line.split_ascii_whitespace()
-        .map(|e| match e {
-            "+" => Op::Add,
-            "*" => Op::Mul,
-            _ => unreachable!("wrong operand {e}"),
-        })
-        .collect()
-}
 
-fn prepare(input: &str) -> Vec<Problem> {
-    let mut lines = input.lines().collect::<Vec<_>>();
-
-    let operations = read_operations(lines.pop().unwrap());
-
-    let mut problems = operations
-        .into_iter()
-        .map(|operation| Problem {
-            operation,
-            operands: Default::default(),
-        })
-        .collect::<Vec<_>>();
-
-    let numbers = |line: &str| {
-        line.split_ascii_whitespace()
-            .map(|e| e.parse::<u64>().unwrap())
-            .collect::<Vec<_>>()
-    };
-    for line in lines {
-        for (i, operand) in numbers(line).into_iter().enumerate() {
-            problems[i].operands.push(operand);
-        }
+    /// Return `None` when there are no operands, or the result does not fit, is negative or
+    /// divides by zero.
+    fn eval(&self) -> Option<u64> {
+        self.expr()?.eval().ok()
     }
+}
 
-    problems
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum WorksheetError {
+    /// The last line holds no operator.
+    MissingOperators,
+    /// A character of the operator line is not an operator.
+    UnknownOperator { column: usize, found: char },
+    /// A character of a number line is neither a digit nor a space.
+    UnexpectedCharacter {
+        line: usize,
+        column: usize,
+        found: char,
+    },
+    /// The operator does not start a new problem: the column before it is not blank, or some
+    /// digits come before the first operator.
+    MisalignedOperator { column: usize },
+    /// Some digits of a number line are separated by spaces within a problem.
+    SplitNumber { line: usize, column: usize },
 }
 
-fn solve_part1(input: &str) -> u64 {
-    let problems = prepare(input);
-    problems.into_iter().map(|problem| problem.eval()).sum()
+/// The numbers of a worksheet, with the columns of each problem.
+///
+/// Each problem starts at the column of its operator and ends before the blank column preceding
+/// the next operator.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Worksheet {
+    /// The number lines, padded with spaces to the same width.
+    rows: Vec<Vec<char>>,
+    blocks: Vec<(BinOp, Range<usize>)>,
 }
 
-fn prepare_transposed(input: &str) -> Vec<Problem> {
-    let mut lines = input.lines().collect::<Vec<_>>();
+impl Worksheet {
+    fn parse(input: &str) -> Result<Self, WorksheetError> {
+        let mut lines = input.lines().collect::<Vec<_>>();
+        while lines.last().is_some_and(|line| line.trim().is_empty()) {
+            lines.pop();
+        }
+        let operators = lines.pop().ok_or(WorksheetError::MissingOperators)?;
+        let width = lines
+            .iter()
+            .chain([&operators])
+            .map(|line| line.chars().count())
+            .max()
+            .unwrap();
 
-    let mut operations = read_operations(lines.pop().unwrap());
-    let mut lines = lines
-        .into_iter()
-        .map(|line| line.chars().collect::<Vec<_>>())
-        .collect::<Vec<_>>();
-
-    let mut problems = vec![];
-    let mut operands = vec![];
-    // read columns from right to left
-    for _ in 0..lines.first().unwrap().len() {
-        let mut o = 0;
-        for line in &mut lines {
-            if let Some(d) = line.pop().unwrap().to_digit(10) {
-                o = 10 * o + d;
+        let mut rows = vec![];
+        for (l, line) in lines.iter().enumerate() {
+            let mut row = line.chars().collect::<Vec<_>>();
+            if let Some(column) = row.iter().position(|c| *c != ' ' && !c.is_ascii_digit()) {
+                return Err(WorksheetError::UnexpectedCharacter {
+                    line: l,
+                    column,
+                    found: row[column],
+                });
             }
+            row.resize(width, ' ');
+            rows.push(row);
         }
+        let is_blank = |column: usize| rows.iter().all(|row| row[column] == ' ');
 
-        if o == 0 {
-            // end of current problem when column is empty (zero)
-            problems.push(Problem {
-                operation: operations.pop().unwrap(),
-                operands: operands.clone(),
-            });
-            operands.clear();
-        } else {
-            operands.push(o as u64);
+        let mut starts = vec![];
+        for (column, c) in operators.chars().enumerate().filter(|(_, c)| *c != ' ') {
+            let op = BinOp::try_from(c)
+                .map_err(|found| WorksheetError::UnknownOperator { column, found })?;
+            starts.push((op, column));
+        }
+        let first = starts.first().ok_or(WorksheetError::MissingOperators)?.1;
+        if !(0..first).all(is_blank) {
+            return Err(WorksheetError::MisalignedOperator { column: first });
+        }
+
+        let mut blocks = vec![];
+        for (i, (op, start)) in starts.iter().enumerate() {
+            let end = match starts.get(i + 1) {
+                Some((_, next)) if next - 1 > *start && is_blank(next - 1) => next - 1,
+                Some((_, next)) => {
+                    return Err(WorksheetError::MisalignedOperator { column: *next });
+                }
+                None => width,
+            };
+            for (line, row) in rows.iter().enumerate() {
+                let text = row[*start..end].iter().collect::<String>();
+                if let Some(gap) = text.trim().find(' ') {
+                    let column = start + text.find(|c| c != ' ').unwrap() + gap;
+                    return Err(WorksheetError::SplitNumber { line, column });
+                }
+            }
+            blocks.push((*op, *start..end));
         }
+        Ok(Worksheet { rows, blocks })
     }
 
-    if !operands.is_empty() {
-        // one last problem
-        problems.push(Problem {
-            operation: operations.pop().unwrap(),
-            operands: operands.clone(),
-        });
+    /// Read the numbers along the lines, from top to bottom.
+    fn horizontal(&self) -> Vec<Problem> {
+        self.blocks
+            .iter()
+            .map(|(operation, columns)| Problem {
+                operation: *operation,
+                operands: self
+                    .rows
+                    .iter()
+                    .filter_map(|row| read_number(row[columns.clone()].iter()))
+                    .collect(),
+            })
+            .collect()
     }
 
+    /// Read the numbers along the columns, from top to bottom, the columns being read from right
+    /// to left.
+    fn vertical(&self) -> Vec<Problem> {
+        self.blocks
+            .iter()
+            .map(|(operation, columns)| Problem {
+                operation: *operation,
+                operands: columns
+                    .clone()
+                    .rev()
+                    .filter_map(|column| read_number(self.rows.iter().map(|row| &row[column])))
+                    .collect(),
+            })
+            .collect()
+    }
+}
+
+/// Read the digits as a decimal number, skipping spaces. Return `None` if there is no digit.
+fn read_number<'a, I: Iterator<Item = &'a char>>(chars: I) -> Option<u64> {
+    chars
+        .filter_map(|c| c.to_digit(10))
+        .fold(None, |n, d| Some(10 * n.unwrap_or(0) + u64::from(d)))
+}
+
+fn grand_total(problems: Vec<Problem>) -> u64 {
     problems
+        .into_iter()
+        .map(|problem| problem.eval().unwrap())
+        .sum()
+}
+
+fn solve_part1(input: &str) -> u64 {
+    grand_total(Worksheet::parse(input).unwrap().horizontal())
 }
 
 fn solve_part2(input: &str) -> u64 {
-    let problems = prepare_transposed(input);
-    problems.into_iter().map(|problem| problem.eval()).sum()
+    grand_total(Worksheet::parse(input).unwrap().vertical())
 }
 
 pub fn solve(input: String) -> SolutionPair {
@@ -135,4 +191,86 @@ mod tests {
     fn example_part2() {
         assert_eq!(solve_part2(EXAMPLE_INPUT), 3263827);
     }
+
+    #[test]
+    fn readings() {
+        let worksheet = Worksheet::parse(EXAMPLE_INPUT).unwrap();
+        assert_eq!(
+            worksheet.blocks,
+            vec![
+                (BinOp::Mul, 0..3),
+                (BinOp::Add, 4..7),
+                (BinOp::Mul, 8..11),
+                (BinOp::Add, 12..15)
+            ]
+        );
+        assert_eq!(worksheet.horizontal()[1].operands, vec![328, 64, 98]);
+        assert_eq!(worksheet.vertical()[1].operands, vec![8, 248, 369]);
+    }
+
+    #[test]
+    fn column_of_zeros() {
+        let input = "
+10 7
+20 1
++  ^
+";
+        let worksheet = Worksheet::parse(input).unwrap();
+        assert_eq!(worksheet.horizontal()[0].operands, vec![10, 20]);
+        assert_eq!(worksheet.vertical()[0].operands, vec![0, 12]);
+        assert_eq!(solve_part1(input), 30 + 7);
+        assert_eq!(solve_part2(input), 12 + 71);
+    }
+
+    #[test]
+    fn operators() {
+        let problem = |operation, operands: &[u64]| Problem {
+            operation,
+            operands: operands.to_vec(),
+        };
+        assert_eq!(problem(BinOp::Sub, &[10, 3, 2]).eval(), Some(5));
+        assert_eq!(problem(BinOp::Div, &[100, 5, 3]).eval(), Some(6));
+        assert_eq!(problem(BinOp::Div, &[1, 0]).eval(), None);
+        assert_eq!(problem(BinOp::Pow, &[2, 3, 2]).eval(), Some(64));
+        assert_eq!(problem(BinOp::Mul, &[u64::MAX, 2]).eval(), None);
+        assert_eq!(problem(BinOp::Add, &[]).eval(), None);
+        // an operator over a blank column block has no operands.
+        let worksheet = Worksheet::parse("1  \n+ *").unwrap();
+        assert_eq!(worksheet.horizontal()[1].eval(), None);
+    }
+
+    #[test]
+    fn malformed() {
+        assert_eq!(
+            Worksheet::parse("\n    \n"),
+            Err(WorksheetError::MissingOperators)
+        );
+        assert_eq!(
+            Worksheet::parse("1 2\n+ %"),
+            Err(WorksheetError::UnknownOperator {
+                column: 2,
+                found: '%'
+            })
+        );
+        assert_eq!(
+            Worksheet::parse("1 x\n+ *"),
+            Err(WorksheetError::UnexpectedCharacter {
+                line: 0,
+                column: 2,
+                found: 'x'
+            })
+        );
+        assert_eq!(
+            Worksheet::parse("12 3\n+ * "),
+            Err(WorksheetError::MisalignedOperator { column: 2 })
+        );
+        assert_eq!(
+            Worksheet::parse("1 3\n +*"),
+            Err(WorksheetError::MisalignedOperator { column: 1 })
+        );
+        assert_eq!(
+            Worksheet::parse("1 3 4\n+   *"),
+            Err(WorksheetError::SplitNumber { line: 0, column: 1 })
+        );
+    }
 }
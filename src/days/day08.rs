@@ -1,90 +1,44 @@
+use crate::etc::graph::{Forest, Stop, kruskal};
+use crate::etc::kdtree::KdTree;
+use crate::etc::vector::Vec3;
 use crate::{Solution, SolutionPair};
-use partitions::PartitionVec;
 
-#[derive(Copy, Clone, Debug, PartialEq, Eq)]
-struct Point(i64, i64, i64);
-
-impl Point {
-    fn distance(p: &Point, q: &Point) -> f64 {
-        let d = (p.0 - q.0).pow(2) + (p.1 - q.1).pow(2) + (p.2 - q.2).pow(2);
-        (d as f64).sqrt()
-    }
-}
-
-fn prepare(input: &str) -> Vec<Point> {
+fn prepare(input: &str) -> Vec<Vec3> {
     input
         .split_ascii_whitespace()
-        .filter_map(|line| {
-            if line.is_empty() {
-                None
-            } else {
-                let mut xyz = line.split(',').map(|e| e.parse::<i64>().unwrap());
-                let x = xyz.next().unwrap();
-                let y = xyz.next().unwrap();
-                let z = xyz.next().unwrap();
-                Some(Point(x, y, z))
-            }
-        })
+        .filter(|line| !line.is_empty())
+        .map(|line| line.parse().unwrap())
         .collect()
 }
 
-fn solve_part1(input: &str, cables: usize) -> usize {
-    let jboxes = prepare(input);
-    let mut partitions = PartitionVec::<Point>::new();
-    jboxes.iter().for_each(|jbox| partitions.push(jbox.clone()));
-
-    let mut distances = vec![];
-    for i in 0..(jboxes.len() - 1) {
-        let p = &jboxes[i];
-        for j in (i + 1)..jboxes.len() {
-            let q = &jboxes[j];
-            let dist = Point::distance(p, q);
-            distances.push((i, j, dist));
-        }
-    }
-
-    distances.sort_by(|(_, _, d1), (_, _, d2)| d1.partial_cmp(d2).unwrap());
-    for (i, j, _) in distances.iter().take(cables){
-        partitions.union(*i, *j);
-    }
-
-    let mut sizes = partitions
-        .all_sets()
-        .map(|set| set.count())
-        .collect::<Vec<_>>();
-    sizes.sort();
-    sizes.pop().unwrap() * sizes.pop().unwrap() * sizes.pop().unwrap()
+/// Return the product of the sizes of the 3 largest circuits, or of all of them if there are
+/// fewer.
+fn largest_circuits(circuits: &Forest<i64>) -> usize {
+    circuits.component_sizes().iter().take(3).product()
 }
 
-fn solve_part2(input: &str) -> u64 {
-    let jboxes = prepare(input);
-    let mut partitions = PartitionVec::<Point>::new();
-    jboxes.iter().for_each(|jbox| partitions.push(jbox.clone()));
-
-    let mut distances = vec![];
-    for i in 0..(jboxes.len() - 1) {
-        let p = &jboxes[i];
-        for j in (i + 1)..jboxes.len() {
-            let q = &jboxes[j];
-            let dist = Point::distance(p, q);
-            distances.push((i, j, dist));
-        }
-    }
-
-    distances.sort_by(|(_, _, d1), (_, _, d2)| d1.partial_cmp(d2).unwrap());
-    for (i, j, _) in &distances {
-        partitions.union(*i, *j);
-        if partitions.amount_of_sets() == 1 {
-            return u64::try_from(jboxes[*i].0 * jboxes[*j].0).unwrap();
-        }
-    }
+/// Return the product of the X coordinates of the junction boxes joined by the last cable.
+fn last_cable(jboxes: &[Vec3], circuits: &Forest<i64>) -> u64 {
+    let (i, j, _) = circuits.last_edge().unwrap();
+    u64::try_from(jboxes[*i].x() * jboxes[*j].x()).unwrap()
+}
 
-    unreachable!()
+/// Connect the closest pairs of junction boxes, return the product of the sizes of the largest
+/// circuits after `cables` pairs, then the product of the X coordinates of the last two boxes
+/// connected once they all form a single circuit.
+///
+/// Both parts share a single pass over the pairs, closest first.
+fn connect(jboxes: &[Vec3], cables: usize) -> (usize, u64) {
+    let tree = KdTree::new(jboxes);
+    let mut pairs = tree.nearest_pairs();
+    let mut circuits = kruskal(jboxes.len(), &mut pairs, Stop::AfterCandidates(cables));
+    let largest = largest_circuits(&circuits);
+    circuits.extend(&mut pairs, Stop::Components(1));
+    (largest, last_cable(jboxes, &circuits))
 }
 
 pub fn solve(input: String) -> SolutionPair {
-    let sol1 = solve_part1(&input, 1000);
-    let sol2 = solve_part2(&input);
+    let (sol1, sol2) = connect(&prepare(&input), 1000);
     (Solution::from(sol1), Solution::from(sol2))
 }
 
@@ -115,12 +69,13 @@ mod tests {
     425,690,689";
 
     #[test]
-    fn example_part1() {
-        assert_eq!(solve_part1(EXAMPLE_INPUT, 10), 40);
+    fn example() {
+        assert_eq!(connect(&prepare(EXAMPLE_INPUT), 10), (40, 25272));
     }
 
     #[test]
-    fn example_part2() {
-        assert_eq!(solve_part2(EXAMPLE_INPUT), 25272);
+    fn two_boxes() {
+        // too few boxes for 3 circuits.
+        assert_eq!(connect(&prepare("2,0,0 3,5,5"), 10), (2, 6));
     }
 }
@@ -1,27 +1,21 @@
-use crate::{Solution, SolutionPair};
+use crate::etc::compress::PlaneCompression;
+use crate::etc::geometry::Polygon;
+use crate::etc::vector::{Vec2, Vector};
+use crate::{Grid, Point, Solution, SolutionPair};
 
-#[derive(Copy, Clone, Debug)]
-struct Tile(i64, i64);
+type Tile = Vec2;
 
 fn prepare(input: &str) -> Vec<Tile> {
     input
         .split_ascii_whitespace()
-        .filter_map(|line| {
-            if line.is_empty() {
-                None
-            } else {
-                line.split_once(',')
-            }
-        })
-        .map(|(a, b)| Tile(a.parse().unwrap(), b.parse().unwrap()))
+        .filter(|line| !line.is_empty())
+        .map(|line| line.parse().unwrap())
         .collect()
 }
 
 /// Take two opposite corners of a rectangle and return the top-left and bottom-left corners.
 fn normalize_corners(a: &Tile, b: &Tile) -> (Tile, Tile) {
-    let top_left = Tile(a.0.min(b.0), a.1.min(b.1));
-    let bottom_right = Tile(a.0.max(b.0), a.1.max(b.1));
-    (top_left, bottom_right)
+    (a.componentwise_min(b), a.componentwise_max(b))
 }
 
 /// Generate all the rectangles as coordinates of the top-left and bottom-right corners and its
@@ -34,8 +28,8 @@ fn all_rectangles(red_tiles: &[Tile]) -> Vec<(Tile, Tile, u64)> {
             let b = red_tiles[j];
             let (top_left, bottom_right) = normalize_corners(&a, &b);
             let area = u64::try_from(
-                ((bottom_right.0 - top_left.0).abs() + 1)
-                    * ((bottom_right.1 - top_left.1).abs() + 1),
+                ((bottom_right.x() - top_left.x()).abs() + 1)
+                    * ((bottom_right.y() - top_left.y()).abs() + 1),
             )
             .unwrap();
             rectangles.push((top_left, bottom_right, area));
@@ -51,61 +45,60 @@ fn solve_part1(input: &str) -> u64 {
     all_rectangles(&red_tiles).pop().unwrap().2
 }
 
-/// Generate all the segments of the outer shape.
-///
-/// Segment's endpoint are normalized as `(top-left, bottom-right)`.
-fn all_segments(red_tiles: &[Tile]) -> Vec<(Tile, Tile)> {
-    let mut segments = red_tiles
-        .windows(2)
-        .map(|arr| {
-            let a = arr[0];
-            let b = arr[1];
-            normalize_corners(&a, &b)
-        })
-        .collect::<Vec<_>>();
-    segments.push(normalize_corners(
-        red_tiles.first().unwrap(),
-        red_tiles.last().unwrap(),
-    ));
-    segments
-}
+/// Compress the coordinates of the vertices of the shape, with a frame of gap cells around it,
+/// and return the compression along with the grid of the compressed cells that are outside the
+/// shape.
+fn outside_cells(shape: &Polygon) -> (PlaneCompression, Grid<bool>) {
+    let vertices = shape.vertices();
+    let (min, max) = vertices
+        .iter()
+        .fold((vertices[0], vertices[0]), |(min, max), t| {
+            (min.componentwise_min(t), max.componentwise_max(t))
+        });
+    let mut coordinates = vertices.to_vec();
+    coordinates.extend([min - Vector([2, 2]), max + Vector([2, 2])]);
+    let plane = PlaneCompression::new(&coordinates, true);
 
-/// Test strict intersection of segment with a rectangle.
-///
-/// Segments are expected to be either vertical or horizontal.
-fn segment_intersects_rectangle(segment: &(Tile, Tile), c1: &Tile, c2: &Tile) -> bool {
-    let s1 = &segment.0;
-    let s2 = &segment.1;
-    // horizontal segment crossing the rectangle ?
-    if s1.1 == s2.1 && s1.1 > c1.1 && s2.1 < c2.1 && s1.0 < c2.0 && s2.0 > c1.0 {
-        return true;
+    let mut boundary = Grid::default(plane.ys.len(), plane.xs.len());
+    for (a, b) in shape.edges() {
+        let (a, b) = (plane.compress(&a).unwrap(), plane.compress(&b).unwrap());
+        for line in a.0..=b.0 {
+            for column in a.1..=b.1 {
+                boundary.update(&Point(line, column), true);
+            }
+        }
     }
-    // vertical segment crossing the rectangle ?
-    if s1.0 == s2.0 && s1.0 > c1.0 && s2.0 < c2.0 && s1.1 < c2.1 && s2.1 > c1.1 {
-        return true;
+
+    // flood fill from the frame, which is outside the shape.
+    let mut outside = Grid::default(plane.ys.len(), plane.xs.len());
+    for pos in boundary.flood_fill(&Point(0, 0), |on_boundary| !on_boundary) {
+        outside.update(&pos, true);
     }
-    return false;
+    (plane, outside)
 }
 
 fn solve_part2(input: &str) -> u64 {
     let red_tiles = prepare(input);
     let rectangles = all_rectangles(&red_tiles);
-    let segments = all_segments(&red_tiles);
-    // Return the area of the largest rectangle that has no red-red segment crossing its borders.
+    let shape = Polygon::new(red_tiles);
+    let (plane, outside) = outside_cells(&shape);
+    let mut inside_area = plane.weights();
+    for (area, is_outside) in inside_area.items.iter_mut().zip(outside.iter()) {
+        if *is_outside {
+            *area = 0;
+        }
+    }
+    let inside_area = inside_area.prefix_sums();
+    // Return the area of the largest rectangle that lies inside the red and green shape, that is
+    // the largest rectangle whose tiles are all inside.
     //
     // Test from largest rectangle to smallest rectangle.
     rectangles
         .into_iter()
         .rev()
         .find_map(|(c1, c2, area)| {
-            if segments
-                .iter()
-                .any(|segment| segment_intersects_rectangle(segment, &c1, &c2))
-            {
-                None
-            } else {
-                Some(area)
-            }
+            let (c1, c2) = (plane.compress(&c1).unwrap(), plane.compress(&c2).unwrap());
+            (inside_area.sum(&c1, &c2) as u64 == area).then_some(area)
         })
         .unwrap()
 }
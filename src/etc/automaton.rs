@@ -0,0 +1,250 @@
+//! Cellular automata over 2D grids.
+#![allow(dead_code)]
+use super::grid::{Grid, Point};
+use std::collections::HashMap;
+
+/// A cycle in the sequence of states of a grid.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cycle {
+    /// Generation of the first state of the cycle.
+    pub start: usize,
+    /// Number of generations before the state repeats.
+    pub length: usize,
+}
+
+impl<T> Grid<T>
+where
+    T: Clone + PartialEq,
+{
+    /// Compute the next generation of the grid, return the number of cells that changed.
+    ///
+    /// All the cells are updated at once: `rule` is given the current generation, the position of
+    /// a cell and its current value, and returns the value of the cell in the next generation.
+    pub fn evolve<F>(&mut self, rule: F) -> usize
+    where
+        F: Fn(&Grid<T>, &Point, &T) -> T,
+    {
+        let mut next = self.items.clone();
+        let mut changes = 0;
+        for (index, cell) in next.iter_mut().enumerate() {
+            let updated = rule(self, &self.unchecked_position(index), &self.items[index]);
+            if updated != *cell {
+                *cell = updated;
+                changes += 1;
+            }
+        }
+        self.items = next;
+        changes
+    }
+
+    /// Evolve the grid until a generation leaves it unchanged, computing at most
+    /// `max_generations` generations.
+    ///
+    /// Return the number of changed cells of each generation, the last stable generation is not
+    /// included. Return `None` if the grid still changes after `max_generations`, like a rule that
+    /// oscillates; the grid is then left in its last generation, and `find_cycle` may tell more.
+    pub fn run_until_stable<F>(&mut self, rule: F, max_generations: usize) -> Option<Vec<usize>>
+    where
+        F: Fn(&Grid<T>, &Point, &T) -> T,
+    {
+        let mut changes = vec![];
+        for _ in 0..max_generations {
+            match self.evolve(&rule) {
+                0 => return Some(changes),
+                n => changes.push(n),
+            }
+        }
+        None
+    }
+
+    /// Same as `run_until_stable` but only the cells next to a cell that changed in the previous
+    /// generation are re-examined.
+    ///
+    /// `rule` must only depend on the cell and its tchebychev neighbours.
+    pub fn run_until_stable_incremental<F>(
+        &mut self,
+        rule: F,
+        max_generations: usize,
+    ) -> Option<Vec<usize>>
+    where
+        F: Fn(&Grid<T>, &Point, &T) -> T,
+    {
+        let mut changes = vec![];
+        let mut worklist = (0..self.size()).collect::<Vec<_>>();
+        let mut queued = vec![false; self.size()];
+        for _ in 0..max_generations {
+            let updates = worklist
+                .iter()
+                .filter_map(|&index| {
+                    let pos = self.unchecked_position(index);
+                    let updated = rule(self, &pos, &self.items[index]);
+                    (updated != self.items[index]).then_some((index, updated))
+                })
+                .collect::<Vec<_>>();
+            if updates.is_empty() {
+                return Some(changes);
+            }
+            changes.push(updates.len());
+
+            worklist.clear();
+            for (index, updated) in updates {
+                self.items[index] = updated;
+                let pos = self.unchecked_position(index);
+                let mut enqueue = |index: usize| {
+                    if !queued[index] {
+                        queued[index] = true;
+                        worklist.push(index);
+                    }
+                };
+                enqueue(index);
+                self.for_each_tchebychev_neighbour(&pos, |neighbour, _| {
+                    enqueue(self.unchecked_index(&neighbour))
+                });
+            }
+            worklist.iter().for_each(|&index| queued[index] = false);
+        }
+        None
+    }
+}
+
+impl<T> Grid<T>
+where
+    T: Clone + Eq + std::hash::Hash,
+{
+    /// Evolve the grid until a state repeats, or at most `max_generations` times.
+    ///
+    /// When a cycle is found, the grid is left in the first repeated state, that is generation
+    /// `start + length`.
+    pub fn find_cycle<F>(&mut self, rule: F, max_generations: usize) -> Option<Cycle>
+    where
+        F: Fn(&Grid<T>, &Point, &T) -> T,
+    {
+        let mut seen = HashMap::from([(self.items.clone(), 0)]);
+        for generation in 1..=max_generations {
+            self.evolve(&rule);
+            if let Some(start) = seen.insert(self.items.clone(), generation) {
+                return Some(Cycle {
+                    start,
+                    length: generation - start,
+                });
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Cycle;
+    use crate::etc::grid::{Grid, Point};
+
+    fn live_neighbours(grid: &Grid, pos: &Point) -> usize {
+        let mut count = 0;
+        grid.for_each_tchebychev_neighbour(pos, |_, v| {
+            if *v == '#' {
+                count += 1;
+            }
+        });
+        count
+    }
+
+    fn life(grid: &Grid, pos: &Point, cell: &char) -> char {
+        match (cell, live_neighbours(grid, pos)) {
+            ('#', 2 | 3) | ('.', 3) => '#',
+            _ => '.',
+        }
+    }
+
+    /// Rolls with less than 4 neighbouring rolls are removed.
+    fn remove_rolls(grid: &Grid, pos: &Point, cell: &char) -> char {
+        let mut rolls = 0;
+        grid.for_each_tchebychev_neighbour(pos, |_, v| {
+            if *v == '@' {
+                rolls += 1;
+            }
+        });
+        if *cell == '@' && rolls < 4 {
+            '.'
+        } else {
+            *cell
+        }
+    }
+
+    const ROLLS: &str = "
+    ..@@.@@@@.
+    @@@.@.@.@@
+    @@@@@.@.@@
+    @.@@@@..@.
+    @@.@@@@.@@
+    .@@@@@@@.@
+    .@.@.@.@@@
+    @.@@@.@@@@
+    .@@@@@@@@.
+    @.@.@@@.@.";
+
+    #[test]
+    fn evolve() {
+        let mut g = Grid::new(".....\n..#..\n..#..\n..#..\n.....\n");
+        assert_eq!(g.evolve(life), 4);
+        assert_eq!(
+            g.items,
+            Grid::new(".....\n.....\n.###.\n.....\n.....\n").items
+        );
+    }
+
+    #[test]
+    fn run_until_stable() {
+        let mut g = Grid::new(ROLLS);
+        let changes = g.run_until_stable(remove_rolls, 100).unwrap();
+        assert_eq!(changes[0], 13);
+        assert_eq!(changes.iter().sum::<usize>(), 43);
+
+        let mut h = Grid::new(ROLLS);
+        assert_eq!(
+            h.run_until_stable_incremental(remove_rolls, 100),
+            Some(changes.clone())
+        );
+        assert_eq!(g.items, h.items);
+
+        // not enough generations to see the stable one.
+        let mut h = Grid::new(ROLLS);
+        assert_eq!(h.run_until_stable(remove_rolls, changes.len()), None);
+        assert_eq!(g.items, h.items);
+    }
+
+    #[test]
+    fn oscillators_never_stabilise() {
+        let blinker = ".....\n..#..\n..#..\n..#..\n.....\n";
+        let mut g = Grid::new(blinker);
+        assert_eq!(g.run_until_stable(life, 10), None);
+        assert_eq!(g.items, Grid::new(blinker).items);
+
+        let mut g = Grid::new(blinker);
+        assert_eq!(g.run_until_stable_incremental(life, 11), None);
+        assert_eq!(
+            g.items,
+            Grid::new(".....\n.....\n.###.\n.....\n.....\n").items
+        );
+    }
+
+    #[test]
+    fn find_cycle() {
+        let mut g = Grid::new(".....\n..#..\n..#..\n..#..\n.....\n");
+        assert_eq!(
+            g.find_cycle(life, 10),
+            Some(Cycle {
+                start: 0,
+                length: 2
+            })
+        );
+
+        // a glider stuck in the corner of the grid settles into a still life
+        let mut g = Grid::new(".#....\n..#...\n###...\n......\n......\n......\n");
+        let cycle = g.find_cycle(life, 100).unwrap();
+        assert_eq!(cycle.length, 1);
+        assert!(g.find(|v| *v == '#').is_some());
+
+        let mut g = Grid::new("#.\n..\n");
+        assert_eq!(g.find_cycle(|_, _, v| *v, 0), None);
+    }
+}
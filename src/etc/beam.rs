@@ -0,0 +1,281 @@
+//! Light beams travelling through a grid of mirrors and splitters.
+#![allow(dead_code)]
+use super::direction::Direction::{self, *};
+use super::grid::{Grid, Point};
+use std::collections::{HashMap, HashSet};
+
+/// The content of a cell, as seen by a beam.
+///
+/// Optics only act on beams travelling in a cardinal direction, diagonal beams go through them.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum Optic {
+    /// The beam goes through.
+    #[default]
+    Empty,
+    /// `^`: a beam travelling north or south goes on from both cells beside the splitter, one step
+    /// further along its way. The cells beside the splitter are skipped, so that a splitter next to
+    /// it does not split the beam again.
+    SideSplitter,
+    /// `|`: a beam travelling east or west leaves towards both north and south.
+    VerticalSplitter,
+    /// `-`: a beam travelling north or south leaves towards both east and west.
+    HorizontalSplitter,
+    /// `/`: the beam is reflected, east becomes north.
+    Mirror,
+    /// `\`: the beam is reflected, east becomes south.
+    BackMirror,
+}
+
+impl From<char> for Optic {
+    /// Any character which is not an optic is an empty cell, like `.` or a start marker.
+    fn from(value: char) -> Self {
+        match value {
+            '^' => Optic::SideSplitter,
+            '|' => Optic::VerticalSplitter,
+            '-' => Optic::HorizontalSplitter,
+            '/' => Optic::Mirror,
+            '\\' => Optic::BackMirror,
+            _ => Optic::Empty,
+        }
+    }
+}
+
+/// A beam in a cell, travelling in some direction.
+pub type Beam = (Point, Direction);
+
+impl Optic {
+    /// Return the beams leaving the cell at `pos` entered by a beam travelling towards `heading`.
+    /// The new positions may lie outside the grid.
+    pub fn outputs(self, pos: &Point, heading: Direction) -> Vec<Beam> {
+        let vertical = matches!(heading, North | South);
+        let horizontal = matches!(heading, East | West);
+        let forward = |d: Direction| (*pos + d.delta(), d);
+        match self {
+            Optic::SideSplitter if vertical => vec![
+                (*pos + heading.delta() + West.delta(), heading),
+                (*pos + heading.delta() + East.delta(), heading),
+            ],
+            Optic::VerticalSplitter if horizontal => vec![forward(North), forward(South)],
+            Optic::HorizontalSplitter if vertical => vec![forward(West), forward(East)],
+            Optic::Mirror if vertical || horizontal => {
+                vec![forward(match heading {
+                    East => North,
+                    North => East,
+                    West => South,
+                    _ => West,
+                })]
+            }
+            Optic::BackMirror if vertical || horizontal => {
+                vec![forward(match heading {
+                    East => South,
+                    South => East,
+                    West => North,
+                    _ => West,
+                })]
+            }
+            _ => vec![forward(heading)],
+        }
+    }
+}
+
+/// The outcome of following a beam until every part of it leaves the grid or loops.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Propagation {
+    /// Every beam met, each one once.
+    pub visited: HashSet<Beam>,
+    /// Number of splitters which split a beam.
+    pub splits: usize,
+}
+
+impl Propagation {
+    /// Return the cells crossed by at least one beam.
+    pub fn energized(&self) -> HashSet<Point> {
+        self.visited.iter().map(|(pos, _)| *pos).collect()
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum BeamError {
+    /// The beam comes back to a state it already went through, so that it never leaves the grid.
+    Cycle(Beam),
+}
+
+pub struct BeamSimulator {
+    optics: Grid<Optic>,
+}
+
+impl BeamSimulator {
+    pub fn new(grid: &Grid<char>) -> Self {
+        BeamSimulator {
+            optics: grid.new_from(|c| Optic::from(*c)),
+        }
+    }
+
+    /// Return the beams leaving the cell of the given beam, which stay inside the grid.
+    pub fn step(&self, beam: &Beam) -> Vec<Beam> {
+        let mut outputs = self.optics.strict_get(&beam.0).outputs(&beam.0, beam.1);
+        outputs.retain(|(pos, _)| self.optics.valid_position(pos));
+        outputs
+    }
+
+    /// Follow the beam and all the beams it splits into. Loops are followed once.
+    pub fn propagate(&self, start: &Point, heading: Direction) -> Propagation {
+        let mut visited = HashSet::from([(*start, heading)]);
+        let mut splitters = HashSet::new();
+        let mut beams = vec![(*start, heading)];
+        while let Some(beam) = beams.pop() {
+            let outputs = self.optics.strict_get(&beam.0).outputs(&beam.0, beam.1);
+            if outputs.len() > 1 {
+                splitters.insert(beam.0);
+            }
+            for output in outputs {
+                if self.optics.valid_position(&output.0) && visited.insert(output) {
+                    beams.push(output);
+                }
+            }
+        }
+        Propagation {
+            visited,
+            splits: splitters.len(),
+        }
+    }
+
+    /// Return the number of distinct paths the beam may follow until it leaves the grid, each
+    /// splitter offering a choice between its outputs.
+    ///
+    /// Fails if a path loops, since there would be infinitely many of them.
+    pub fn count_timelines(&self, start: &Point, heading: Direction) -> Result<u64, BeamError> {
+        // iterative depth-first search: the beams still on `path` are being counted, finding one
+        // of them again means there is a loop.
+        let mut timelines: HashMap<Beam, u64> = HashMap::new();
+        let mut path = HashSet::new();
+        let mut stack = vec![((*start, heading), false)];
+        while let Some((beam, expanded)) = stack.pop() {
+            let outputs = self.optics.strict_get(&beam.0).outputs(&beam.0, beam.1);
+            if expanded {
+                // a beam leaving the grid is a single timeline, the others are all counted by now.
+                let count = outputs
+                    .iter()
+                    .map(|output| {
+                        if self.optics.valid_position(&output.0) {
+                            timelines[output]
+                        } else {
+                            1
+                        }
+                    })
+                    .sum();
+                timelines.insert(beam, count);
+                path.remove(&beam);
+                continue;
+            }
+            if timelines.contains_key(&beam) {
+                continue;
+            }
+            path.insert(beam);
+            stack.push((beam, true));
+            for output in outputs {
+                if !self.optics.valid_position(&output.0) || timelines.contains_key(&output) {
+                    continue;
+                }
+                if path.contains(&output) {
+                    return Err(BeamError::Cycle(output));
+                }
+                stack.push((output, false));
+            }
+        }
+        Ok(timelines[&(*start, heading)])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BeamError, BeamSimulator, Optic};
+    use crate::etc::direction::Direction::*;
+    use crate::etc::grid::{Grid, Point};
+
+    const CONTRAPTION: &str = r"
+        .|...\....
+        |.-.\.....
+        .....|-...
+        ........|.
+        ..........
+        .........\
+        ..../.\\..
+        .-.-/..|..
+        .|....-|.\
+        ..//.|....";
+
+    #[test]
+    fn optics() {
+        let pos = Point(5, 5);
+        assert_eq!(
+            Optic::Mirror.outputs(&pos, East),
+            vec![(Point(4, 5), North)]
+        );
+        assert_eq!(
+            Optic::BackMirror.outputs(&pos, North),
+            vec![(Point(5, 4), West)]
+        );
+        assert_eq!(
+            Optic::SideSplitter.outputs(&pos, South),
+            vec![(Point(6, 4), South), (Point(6, 6), South)]
+        );
+        assert_eq!(
+            Optic::VerticalSplitter.outputs(&pos, South),
+            vec![(Point(6, 5), South)]
+        );
+        assert_eq!(
+            Optic::Mirror.outputs(&pos, SouthEast),
+            vec![(Point(6, 6), SouthEast)]
+        );
+    }
+
+    #[test]
+    fn energized() {
+        let simulator = BeamSimulator::new(&Grid::new(CONTRAPTION));
+        let propagation = simulator.propagate(&Point(0, 0), East);
+        assert_eq!(propagation.energized().len(), 46);
+        assert!(matches!(
+            simulator.count_timelines(&Point(0, 0), East),
+            Err(BeamError::Cycle(_))
+        ));
+    }
+
+    #[test]
+    fn borders() {
+        // splitters against the border only keep the beams staying inside, the other beams
+        // leave the grid at once.
+        let grid = Grid::new(
+            "
+            .S..
+            ....
+            ^...
+            ...^",
+        );
+        let simulator = BeamSimulator::new(&grid);
+        assert_eq!(
+            simulator.step(&(Point(2, 0), South)),
+            vec![(Point(3, 1), South)]
+        );
+        assert_eq!(simulator.propagate(&Point(0, 1), South).splits, 0);
+        assert_eq!(simulator.count_timelines(&Point(0, 0), South), Ok(2));
+        assert_eq!(simulator.propagate(&Point(0, 3), South).splits, 1);
+        assert_eq!(simulator.count_timelines(&Point(0, 3), South), Ok(2));
+    }
+
+    #[test]
+    fn adjacent_side_splitters() {
+        // the side beams go on from the next line, the second splitter is never reached.
+        let grid = Grid::new(
+            "
+            .S..
+            .^^.
+            ....",
+        );
+        let simulator = BeamSimulator::new(&grid);
+        let propagation = simulator.propagate(&Point(0, 1), South);
+        assert_eq!(propagation.splits, 1);
+        assert!(!propagation.energized().contains(&Point(1, 2)));
+        assert_eq!(simulator.count_timelines(&Point(0, 1), South), Ok(2));
+    }
+}
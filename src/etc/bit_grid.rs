@@ -0,0 +1,373 @@
+//! Bit-packed boolean 2D grid.
+#![allow(dead_code)]
+use super::grid::{ALL_DIRECTIONS, Grid, Point};
+
+/// A 2D grid of booleans storing one bit per cell, coordinates are `Point(line, column)` like in
+/// `Grid`.
+///
+/// Each line is stored as a sequence of 64-bit words, where the bit `c % 64` of the word `c / 64`
+/// is the cell of column `c`. Bits past the last column are always cleared, so that whole words
+/// can be combined and counted.
+#[derive(Clone, PartialEq, Eq)]
+pub struct BitGrid {
+    pub lines: usize,
+    pub columns: usize,
+    words_per_line: usize,
+    words: Vec<u64>,
+}
+
+/// Number of set neighbours of each cell, stored as 4 bit planes: plane `i` holds bit `i` of the
+/// count of every cell.
+pub struct NeighbourCounts {
+    planes: [BitGrid; 4],
+}
+
+impl BitGrid {
+    /// Create a grid where all cells are cleared.
+    pub fn new(lines: usize, columns: usize) -> Self {
+        let words_per_line = columns.div_ceil(64);
+        BitGrid {
+            lines,
+            columns,
+            words_per_line,
+            words: vec![0; lines * words_per_line],
+        }
+    }
+
+    /// Create a grid where cells are set if `predicate` holds for the same cell of `grid`.
+    pub fn from_grid<T, P>(grid: &Grid<T>, predicate: P) -> Self
+    where
+        P: Fn(&T) -> bool,
+    {
+        let mut bits = BitGrid::new(grid.lines, grid.columns);
+        grid.for_each_with_position(|pos, item| {
+            if predicate(item) {
+                bits.set(&pos, true);
+            }
+        });
+        bits
+    }
+
+    pub fn to_grid(&self) -> Grid<bool> {
+        let mut grid = Grid::default(self.lines, self.columns);
+        for line in 0..self.lines {
+            for column in 0..self.columns {
+                let pos = Point(line as i64, column as i64);
+                grid.update(&pos, self.get(&pos));
+            }
+        }
+        grid
+    }
+
+    pub fn valid_position(&self, pos: &Point) -> bool {
+        pos.0 >= 0 && (pos.0 as usize) < self.lines && pos.1 >= 0 && (pos.1 as usize) < self.columns
+    }
+
+    /// Return the word index and the bit mask of a valid position.
+    fn locate(&self, pos: &Point) -> (usize, u64) {
+        assert!(self.valid_position(pos), "invalid position");
+        let column = pos.1 as usize;
+        (
+            pos.0 as usize * self.words_per_line + column / 64,
+            1 << (column % 64),
+        )
+    }
+
+    /// Mask of the valid bits of the last word of each line.
+    fn last_word_mask(&self) -> u64 {
+        match self.columns % 64 {
+            0 => u64::MAX,
+            r => (1 << r) - 1,
+        }
+    }
+
+    /// Clear the bits past the last column.
+    fn clear_padding(&mut self) {
+        let mask = self.last_word_mask();
+        if self.words_per_line > 0 {
+            self.words
+                .chunks_exact_mut(self.words_per_line)
+                .for_each(|line| *line.last_mut().unwrap() &= mask);
+        }
+    }
+
+    /// Retrieve the cell at the given point, panics if the point is outside the grid.
+    pub fn get(&self, pos: &Point) -> bool {
+        let (word, mask) = self.locate(pos);
+        self.words[word] & mask != 0
+    }
+
+    /// Set or clear the cell at the given point, panics if the point is outside the grid.
+    pub fn set(&mut self, pos: &Point, val: bool) {
+        let (word, mask) = self.locate(pos);
+        if val {
+            self.words[word] |= mask;
+        } else {
+            self.words[word] &= !mask;
+        }
+    }
+
+    /// Return the number of set cells.
+    pub fn count_ones(&self) -> usize {
+        self.words.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    /// Test whether no cell is set.
+    pub fn is_empty(&self) -> bool {
+        self.words.iter().all(|w| *w == 0)
+    }
+
+    /// Iterate over the positions of the set cells, in reading order.
+    pub fn ones(&self) -> impl Iterator<Item = Point> + '_ {
+        self.words.iter().enumerate().flat_map(move |(i, word)| {
+            let line = (i / self.words_per_line) as i64;
+            let base = 64 * (i % self.words_per_line) as i64;
+            let mut word = *word;
+            std::iter::from_fn(move || {
+                if word == 0 {
+                    return None;
+                }
+                let bit = word.trailing_zeros() as i64;
+                word &= word - 1;
+                Some(Point(line, base + bit))
+            })
+        })
+    }
+
+    /// Return a copy of the grid with its content moved by `delta`.
+    ///
+    /// Cells moved past the edges are lost, and uncovered cells are cleared.
+    pub fn shift(&self, delta: &Point) -> BitGrid {
+        let mut shifted = BitGrid::new(self.lines, self.columns);
+        let wpl = self.words_per_line;
+        for line in 0..self.lines {
+            let source = line as i64 - delta.0;
+            if source < 0 || source as usize >= self.lines {
+                continue;
+            }
+            let source = source as usize;
+            shift_words(
+                &self.words[source * wpl..(source + 1) * wpl],
+                &mut shifted.words[line * wpl..(line + 1) * wpl],
+                delta.1,
+            );
+        }
+        shifted.clear_padding();
+        shifted
+    }
+
+    /// Count the set tchebychev neighbours of every cell, all cells being counted at once one
+    /// word at a time.
+    pub fn tchebychev_neighbour_counts(&self) -> NeighbourCounts {
+        let mut planes: [BitGrid; 4] =
+            std::array::from_fn(|_| BitGrid::new(self.lines, self.columns));
+        for delta in &ALL_DIRECTIONS {
+            let shifted = self.shift(delta);
+            for (i, word) in shifted.words.into_iter().enumerate() {
+                // ripple-carry addition of one bit to the 4-bit counter of each cell
+                let mut carry = word;
+                for plane in planes.iter_mut() {
+                    let sum = plane.words[i] ^ carry;
+                    carry &= plane.words[i];
+                    plane.words[i] = sum;
+                }
+            }
+        }
+        NeighbourCounts { planes }
+    }
+}
+
+/// Shift a line of words by `delta` columns towards the higher columns (or towards the lower
+/// columns if `delta` is negative).
+fn shift_words(source: &[u64], target: &mut [u64], delta: i64) {
+    let len = source.len() as i64;
+    let word_shift = delta.div_euclid(64);
+    let bit_shift = delta.rem_euclid(64) as u32;
+    let word = |i: i64| {
+        if 0 <= i && i < len {
+            source[i as usize]
+        } else {
+            0
+        }
+    };
+    for (i, target) in target.iter_mut().enumerate() {
+        let i = i as i64 - word_shift;
+        *target = if bit_shift == 0 {
+            word(i)
+        } else {
+            (word(i) << bit_shift) | (word(i - 1) >> (64 - bit_shift))
+        };
+    }
+}
+
+impl NeighbourCounts {
+    /// Return the cells having exactly `n` set neighbours.
+    pub fn equal(&self, n: u8) -> BitGrid {
+        let mut result = self.planes[0].clone();
+        for (i, word) in result.words.iter_mut().enumerate() {
+            *word = self
+                .planes
+                .iter()
+                .enumerate()
+                .fold(!0, |acc, (bit, plane)| {
+                    if n & (1 << bit) != 0 {
+                        acc & plane.words[i]
+                    } else {
+                        acc & !plane.words[i]
+                    }
+                });
+        }
+        result.clear_padding();
+        result
+    }
+
+    /// Return the cells having strictly less than `n` set neighbours.
+    pub fn less_than(&self, n: u8) -> BitGrid {
+        let mut result = BitGrid::new(self.planes[0].lines, self.planes[0].columns);
+        for count in 0..n.min(9) {
+            result |= &self.equal(count);
+        }
+        result
+    }
+}
+
+macro_rules! impl_bit_op {
+    ($trait_:ident, $method_:ident, $assign_trait_:ident, $assign_method_:ident, $op_:tt) => {
+        impl std::ops::$assign_trait_<&BitGrid> for BitGrid {
+            fn $assign_method_(&mut self, rhs: &BitGrid) {
+                assert_eq!((self.lines, self.columns), (rhs.lines, rhs.columns));
+                self.words
+                    .iter_mut()
+                    .zip(&rhs.words)
+                    .for_each(|(a, b)| *a $op_ *b);
+            }
+        }
+
+        impl std::ops::$trait_ for &BitGrid {
+            type Output = BitGrid;
+
+            fn $method_(self, rhs: &BitGrid) -> BitGrid {
+                let mut result = self.clone();
+                std::ops::$assign_trait_::$assign_method_(&mut result, rhs);
+                result
+            }
+        }
+    };
+}
+
+impl_bit_op!(BitAnd, bitand, BitAndAssign, bitand_assign, &=);
+impl_bit_op!(BitOr, bitor, BitOrAssign, bitor_assign, |=);
+impl_bit_op!(BitXor, bitxor, BitXorAssign, bitxor_assign, ^=);
+
+impl std::ops::Not for &BitGrid {
+    type Output = BitGrid;
+
+    fn not(self) -> BitGrid {
+        let mut result = self.clone();
+        result.words.iter_mut().for_each(|w| *w = !*w);
+        result.clear_padding();
+        result
+    }
+}
+
+impl std::fmt::Debug for BitGrid {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for line in 0..self.lines {
+            for column in 0..self.columns {
+                let set = self.get(&Point(line as i64, column as i64));
+                f.write_str(if set { "#" } else { "." })?;
+            }
+            f.write_str("\n")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::BitGrid;
+    use crate::etc::grid::{Grid, Point};
+
+    fn from_str(input: &str) -> BitGrid {
+        BitGrid::from_grid(&Grid::new(input), |c| *c == '#')
+    }
+
+    #[test]
+    fn get_and_set() {
+        let mut g = BitGrid::new(3, 130);
+        g.set(&Point(2, 129), true);
+        g.set(&Point(0, 64), true);
+        assert!(g.get(&Point(2, 129)));
+        assert!(!g.get(&Point(2, 128)));
+        assert_eq!(g.count_ones(), 2);
+        assert_eq!(
+            g.ones().collect::<Vec<_>>(),
+            vec![Point(0, 64), Point(2, 129)]
+        );
+        g.set(&Point(0, 64), false);
+        assert_eq!(g.count_ones(), 1);
+    }
+
+    #[test]
+    fn set_operations() {
+        let a = from_str("##..\n#.#.\n");
+        let b = from_str("#.#.\n#..#\n");
+        assert_eq!(&a & &b, from_str("#...\n#...\n"));
+        assert_eq!(&a | &b, from_str("###.\n#.##\n"));
+        assert_eq!(&a ^ &b, from_str(".##.\n..##\n"));
+        assert_eq!(!&a, from_str("..##\n.#.#\n"));
+        // padding bits are not counted
+        assert_eq!((!&BitGrid::new(2, 70)).count_ones(), 140);
+    }
+
+    #[test]
+    fn shift() {
+        let g = from_str("#..\n.#.\n..#\n");
+        assert_eq!(g.shift(&Point(0, 1)), from_str(".#.\n..#\n...\n"));
+        assert_eq!(g.shift(&Point(-1, -1)), from_str("#..\n.#.\n...\n"));
+        assert_eq!(g.shift(&Point(3, 0)), BitGrid::new(3, 3));
+
+        let mut wide = BitGrid::new(1, 200);
+        wide.set(&Point(0, 63), true);
+        wide.set(&Point(0, 199), true);
+        let shifted = wide.shift(&Point(0, 70));
+        assert_eq!(shifted.ones().collect::<Vec<_>>(), vec![Point(0, 133)]);
+        let shifted = wide.shift(&Point(0, -64));
+        assert_eq!(shifted.ones().collect::<Vec<_>>(), vec![Point(0, 135)]);
+    }
+
+    #[test]
+    fn neighbour_counts() {
+        let input = "#.##.#\n.####.\n##..##\n#.#.#.\n";
+        let grid = Grid::new(input);
+        let counts = from_str(input).tchebychev_neighbour_counts();
+        for n in 0..=8 {
+            let mut expected = BitGrid::new(grid.lines, grid.columns);
+            grid.for_each_with_position(|pos, _| {
+                let mut count = 0;
+                grid.for_each_tchebychev_neighbour(&pos, |_, v| {
+                    if *v == '#' {
+                        count += 1;
+                    }
+                });
+                if count == n {
+                    expected.set(&pos, true);
+                }
+            });
+            assert_eq!(counts.equal(n), expected, "{n} neighbours");
+        }
+        assert_eq!(counts.less_than(9).count_ones(), grid.size());
+    }
+
+    #[test]
+    fn game_of_life_blinker() {
+        let blinker = from_str(".....\n..#..\n..#..\n..#..\n.....\n");
+        let counts = blinker.tchebychev_neighbour_counts();
+        let survive = &blinker & &(&counts.equal(2) | &counts.equal(3));
+        let born = &!&blinker & &counts.equal(3);
+        assert_eq!(
+            &survive | &born,
+            from_str(".....\n.....\n.###.\n.....\n.....\n")
+        );
+    }
+}
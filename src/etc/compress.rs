@@ -0,0 +1,173 @@
+//! Coordinate compression of large sparse coordinate sets.
+#![allow(dead_code)]
+use super::grid::{Grid, Point};
+use super::vector::{Vec2, Vector};
+use std::ops::RangeInclusive;
+
+/// Maps the distinct values of a coordinate to dense cell indices.
+///
+/// Each distinct value gets its own cell. With gaps, the values strictly between two consecutive
+/// distinct values also get a single cell, so that the cells cover the whole range of values.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Compression {
+    cells: Vec<RangeInclusive<i64>>,
+}
+
+impl Compression {
+    pub fn new<I: IntoIterator<Item = i64>>(values: I, gaps: bool) -> Self {
+        let mut values = values.into_iter().collect::<Vec<_>>();
+        values.sort();
+        values.dedup();
+        let mut cells = vec![];
+        for (i, value) in values.iter().enumerate() {
+            if gaps && i > 0 && values[i - 1] + 1 < *value {
+                cells.push(values[i - 1] + 1..=value - 1);
+            }
+            cells.push(*value..=*value);
+        }
+        Compression { cells }
+    }
+
+    /// Return the number of cells.
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    /// Return the index of the cell covering the given value.
+    pub fn index(&self, value: i64) -> Option<usize> {
+        let index = self.cells.partition_point(|cell| cell.end() < &value);
+        self.cells
+            .get(index)
+            .filter(|cell| cell.contains(&value))
+            .map(|_| index)
+    }
+
+    /// Return the values covered by the given cell.
+    pub fn range(&self, index: usize) -> &RangeInclusive<i64> {
+        &self.cells[index]
+    }
+
+    /// Return the number of values covered by the given cell.
+    pub fn width(&self, index: usize) -> i64 {
+        self.cells[index].end() - self.cells[index].start() + 1
+    }
+}
+
+/// Compression of both coordinates of a set of points, `x` being mapped to grid columns and `y`
+/// to grid lines.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlaneCompression {
+    pub xs: Compression,
+    pub ys: Compression,
+}
+
+impl PlaneCompression {
+    pub fn new(points: &[Vec2], gaps: bool) -> Self {
+        PlaneCompression {
+            xs: Compression::new(points.iter().map(|p| p.x()), gaps),
+            ys: Compression::new(points.iter().map(|p| p.y()), gaps),
+        }
+    }
+
+    /// Return the position of the cell covering the given point.
+    pub fn compress(&self, p: &Vec2) -> Option<Point> {
+        Some(Point(
+            self.ys.index(p.y())? as i64,
+            self.xs.index(p.x())? as i64,
+        ))
+    }
+
+    /// Return the real top-left and bottom-right corners of the given cell.
+    pub fn decompress(&self, pos: &Point) -> (Vec2, Vec2) {
+        let xs = self.xs.range(pos.1 as usize);
+        let ys = self.ys.range(pos.0 as usize);
+        (
+            Vector([*xs.start(), *ys.start()]),
+            Vector([*xs.end(), *ys.end()]),
+        )
+    }
+
+    /// Build the compressed grid, the value of each cell is computed by `f` from the real
+    /// top-left corner of the cell.
+    pub fn grid<T, F>(&self, f: F) -> Grid<T>
+    where
+        F: Fn(&Vec2) -> T,
+    {
+        let items = (0..self.ys.len())
+            .flat_map(|line| {
+                (0..self.xs.len()).map(move |column| Point(line as i64, column as i64))
+            })
+            .map(|pos| f(&self.decompress(&pos).0))
+            .collect();
+        Grid {
+            lines: self.ys.len(),
+            columns: self.xs.len(),
+            items,
+        }
+    }
+
+    /// Return the grid of the real area covered by each cell.
+    pub fn weights(&self) -> Grid<i64> {
+        let mut weights = Grid::default(self.ys.len(), self.xs.len());
+        for line in 0..self.ys.len() {
+            for column in 0..self.xs.len() {
+                let area = self.ys.width(line) * self.xs.width(column);
+                weights.update(&Point(line as i64, column as i64), area);
+            }
+        }
+        weights
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Compression, PlaneCompression};
+    use crate::etc::grid::Point;
+    use crate::etc::vector::Vector;
+
+    #[test]
+    fn compression() {
+        let c = Compression::new([100, 5, 6, 100, 1_000_000], false);
+        assert_eq!(c.len(), 4);
+        assert_eq!(c.index(6), Some(1));
+        assert_eq!(c.index(7), None);
+        assert_eq!(c.range(3), &(1_000_000..=1_000_000));
+
+        let c = Compression::new([100, 5, 6, 100, 1_000_000], true);
+        assert_eq!(c.len(), 6);
+        assert_eq!(c.index(6), Some(1));
+        assert_eq!(c.index(7), Some(2));
+        assert_eq!(c.index(99), Some(2));
+        assert_eq!(c.index(100), Some(3));
+        assert_eq!(c.width(4), 1_000_000 - 101);
+        assert_eq!(c.index(4), None);
+        assert_eq!(c.index(2_000_000), None);
+    }
+
+    #[test]
+    fn plane() {
+        let points = [Vector([10, 0]), Vector([0, 1000]), Vector([20, 10])];
+        let plane = PlaneCompression::new(&points, true);
+        assert_eq!((plane.ys.len(), plane.xs.len()), (5, 5));
+        assert_eq!(plane.compress(&Vector([20, 10])), Some(Point(2, 4)));
+        assert_eq!(plane.compress(&Vector([15, 500])), Some(Point(3, 3)));
+        assert_eq!(
+            plane.decompress(&Point(3, 3)),
+            (Vector([11, 11]), Vector([19, 999]))
+        );
+
+        let weights = plane.weights();
+        assert_eq!(weights.items.iter().sum::<i64>(), 21 * 1001);
+        let sums = weights.prefix_sums();
+        assert_eq!(sums.sum(&Point(0, 0), &Point(4, 4)), 21 * 1001);
+        assert_eq!(sums.sum(&Point(3, 3), &Point(3, 3)), 9 * 989);
+        assert_eq!(sums.sum(&Point(1, 0), &Point(2, 1)), 10 * 10);
+
+        let grid = plane.grid(|corner| corner.x() + corner.y());
+        assert_eq!(grid.at(3, 3), Some(&22));
+    }
+}
@@ -0,0 +1,220 @@
+//! Headings on a square grid and walking robots.
+#![allow(dead_code)]
+use super::grid::Point;
+
+/// One of the 8 headings on a square grid, north being towards the first line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Direction {
+    North,
+    NorthEast,
+    East,
+    SouthEast,
+    South,
+    SouthWest,
+    West,
+    NorthWest,
+}
+
+use Direction::*;
+
+impl Direction {
+    /// The 4 cardinal directions, clockwise from north.
+    pub const CARDINALS: [Direction; 4] = [North, East, South, West];
+
+    /// The 8 directions, clockwise from north.
+    pub const ALL: [Direction; 8] = [
+        North, NorthEast, East, SouthEast, South, SouthWest, West, NorthWest,
+    ];
+
+    /// Rotate clockwise by `eighths` eighths of a turn.
+    fn rotate(self, eighths: usize) -> Self {
+        Self::ALL[(self as usize + eighths) % 8]
+    }
+
+    pub fn is_cardinal(self) -> bool {
+        (self as usize).is_multiple_of(2)
+    }
+
+    /// Quarter turn clockwise.
+    pub fn turn_right(self) -> Self {
+        self.rotate(2)
+    }
+
+    /// Quarter turn counterclockwise.
+    pub fn turn_left(self) -> Self {
+        self.rotate(6)
+    }
+
+    /// Eighth of a turn clockwise.
+    pub fn turn_right_45(self) -> Self {
+        self.rotate(1)
+    }
+
+    /// Eighth of a turn counterclockwise.
+    pub fn turn_left_45(self) -> Self {
+        self.rotate(7)
+    }
+
+    pub fn opposite(self) -> Self {
+        self.rotate(4)
+    }
+
+    /// Return the `(delta-line, delta-column)` of a single step in this direction.
+    pub fn delta(self) -> Point {
+        match self {
+            North => Point::NORTH,
+            NorthEast => Point::NORTH_EAST,
+            East => Point::EAST,
+            SouthEast => Point::SOUTH_EAST,
+            South => Point::SOUTH,
+            SouthWest => Point::SOUTH_WEST,
+            West => Point::WEST,
+            NorthWest => Point::NORTH_WEST,
+        }
+    }
+}
+
+impl From<Direction> for Point {
+    fn from(value: Direction) -> Self {
+        value.delta()
+    }
+}
+
+/// Convert a single step delta to a direction, the delta is given back when it is not a step.
+impl TryFrom<Point> for Direction {
+    type Error = Point;
+
+    fn try_from(value: Point) -> Result<Self, Self::Error> {
+        Self::ALL
+            .into_iter()
+            .find(|d| d.delta() == value)
+            .ok_or(value)
+    }
+}
+
+/// Read a cardinal direction written as an arrow `^>v<`, a compass point `NESW` or a relative
+/// move `UDLR`. The character is given back when it is not a direction.
+impl TryFrom<char> for Direction {
+    type Error = char;
+
+    fn try_from(value: char) -> Result<Self, Self::Error> {
+        match value {
+            '^' | 'N' | 'U' => Ok(North),
+            '>' | 'E' | 'R' => Ok(East),
+            'v' | 'S' | 'D' => Ok(South),
+            '<' | 'W' | 'L' => Ok(West),
+            _ => Err(value),
+        }
+    }
+}
+
+/// A position along with a heading, like a robot walking on a grid.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Pose {
+    pub position: Point,
+    pub heading: Direction,
+}
+
+impl Pose {
+    pub fn new(position: Point, heading: Direction) -> Self {
+        Pose { position, heading }
+    }
+
+    /// Return the position one step ahead.
+    pub fn ahead(&self) -> Point {
+        self.position + self.heading.delta()
+    }
+
+    /// Move `steps` steps along the heading, backwards if `steps` is negative.
+    pub fn advance(&self, steps: i64) -> Self {
+        Pose {
+            position: self.position + self.heading.delta() * steps,
+            heading: self.heading,
+        }
+    }
+
+    pub fn turn_left(&self) -> Self {
+        Pose {
+            position: self.position,
+            heading: self.heading.turn_left(),
+        }
+    }
+
+    pub fn turn_right(&self) -> Self {
+        Pose {
+            position: self.position,
+            heading: self.heading.turn_right(),
+        }
+    }
+
+    pub fn turn_around(&self) -> Self {
+        Pose {
+            position: self.position,
+            heading: self.heading.opposite(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Direction, Direction::*, Pose};
+    use crate::etc::grid::{ALL_DIRECTIONS, Point, TAXICAB_DIRECTIONS};
+
+    #[test]
+    fn turns() {
+        assert_eq!(North.turn_right(), East);
+        assert_eq!(North.turn_left(), West);
+        assert_eq!(West.turn_right(), North);
+        assert_eq!(NorthEast.turn_right(), SouthEast);
+        assert_eq!(North.turn_right_45(), NorthEast);
+        assert_eq!(North.turn_left_45(), NorthWest);
+        assert_eq!(SouthWest.opposite(), NorthEast);
+        assert!(South.is_cardinal());
+        assert!(!SouthEast.is_cardinal());
+    }
+
+    #[test]
+    fn turns_match_point_rotations() {
+        for d in Direction::ALL {
+            assert_eq!(d.turn_right().delta(), d.delta().rotate_90_clockwise());
+            assert_eq!(
+                d.turn_left().delta(),
+                d.delta().rotate_90_counterclockwise()
+            );
+            assert_eq!(d.opposite().delta(), d.delta().rotate_180());
+        }
+    }
+
+    #[test]
+    fn points() {
+        for delta in ALL_DIRECTIONS {
+            assert_eq!(Point::from(Direction::try_from(delta).unwrap()), delta);
+        }
+        for delta in TAXICAB_DIRECTIONS {
+            assert!(Direction::try_from(delta).unwrap().is_cardinal());
+        }
+        assert_eq!(Direction::try_from(Point(2, 0)), Err(Point(2, 0)));
+    }
+
+    #[test]
+    fn chars() {
+        let parse = |s: &str| {
+            s.chars()
+                .map(|c| Direction::try_from(c).unwrap())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(parse("^>v<"), vec![North, East, South, West]);
+        assert_eq!(parse("NESW"), parse("URDL"));
+        assert_eq!(Direction::try_from('x'), Err('x'));
+    }
+
+    #[test]
+    fn pose() {
+        let pose = Pose::new(Point(5, 5), North);
+        assert_eq!(pose.ahead(), Point(4, 5));
+        let pose = pose.advance(3).turn_right().advance(2);
+        assert_eq!(pose, Pose::new(Point(2, 7), East));
+        let pose = pose.turn_around().advance(-1).turn_left();
+        assert_eq!(pose, Pose::new(Point(2, 8), South));
+    }
+}
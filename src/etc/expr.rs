@@ -0,0 +1,319 @@
+//! Arithmetic expressions: syntax tree, infix parser and checked evaluation.
+#![allow(dead_code)]
+use num::traits::{CheckedAdd, CheckedDiv, CheckedMul, CheckedSub, One, ToPrimitive, Zero};
+use std::str::FromStr;
+
+/// Numbers an expression can be evaluated with, like `u64`, `u128` or `num::BigInt`.
+pub trait Number:
+    Clone + Zero + One + CheckedAdd + CheckedSub + CheckedMul + CheckedDiv + ToPrimitive + FromStr
+{
+}
+
+impl<T> Number for T where
+    T: Clone
+        + Zero
+        + One
+        + CheckedAdd
+        + CheckedSub
+        + CheckedMul
+        + CheckedDiv
+        + ToPrimitive
+        + FromStr
+{
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum BinOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Pow,
+}
+
+impl TryFrom<char> for BinOp {
+    type Error = char;
+
+    fn try_from(value: char) -> Result<Self, Self::Error> {
+        match value {
+            '+' => Ok(BinOp::Add),
+            '-' => Ok(BinOp::Sub),
+            '*' => Ok(BinOp::Mul),
+            '/' => Ok(BinOp::Div),
+            '^' => Ok(BinOp::Pow),
+            _ => Err(value),
+        }
+    }
+}
+
+/// How binary operators bind when parsing infix text.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Precedence {
+    /// All operators bind the same and are applied from left to right.
+    LeftToRight,
+    /// Additions and subtractions bind tighter than multiplications and divisions, powers bind
+    /// tightest.
+    AddBeforeMul,
+    /// The usual rules: powers, then multiplications and divisions, then additions and
+    /// subtractions. Powers group from right to left.
+    Standard,
+}
+
+impl Precedence {
+    /// Return how tight the operator binds, and whether it groups from right to left.
+    fn binding(self, op: BinOp) -> (u8, bool) {
+        match (self, op) {
+            (Precedence::LeftToRight, _) => (1, false),
+            (_, BinOp::Pow) => (3, self == Precedence::Standard),
+            (Precedence::AddBeforeMul, BinOp::Add | BinOp::Sub) => (2, false),
+            (Precedence::AddBeforeMul, _) => (1, false),
+            (Precedence::Standard, BinOp::Mul | BinOp::Div) => (2, false),
+            (Precedence::Standard, _) => (1, false),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Expr<T> {
+    Num(T),
+    Binary(BinOp, Box<Expr<T>>, Box<Expr<T>>),
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum EvalError {
+    DivisionByZero,
+    /// An intermediate result cannot be represented, like a negative `u64`.
+    OutOfRange,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ParseExprError {
+    /// The character at the given byte offset is not part of an expression.
+    UnexpectedCharacter(usize, char),
+    /// A number or an operator is missing at the given byte offset.
+    UnexpectedToken(usize),
+    UnexpectedEnd,
+    /// The number does not fit the type.
+    InvalidNumber(String),
+}
+
+impl<T: Number> Expr<T> {
+    pub fn binary(op: BinOp, left: Expr<T>, right: Expr<T>) -> Self {
+        Expr::Binary(op, Box::new(left), Box::new(right))
+    }
+
+    /// Apply the operator to the operands from left to right: `((a op b) op c) ...`.
+    ///
+    /// Return `None` if there are no operands.
+    pub fn chain<I: IntoIterator<Item = T>>(op: BinOp, operands: I) -> Option<Self> {
+        let mut operands = operands.into_iter().map(Expr::Num);
+        let first = operands.next()?;
+        Some(operands.fold(first, |left, right| Expr::binary(op, left, right)))
+    }
+
+    pub fn eval(&self) -> Result<T, EvalError> {
+        match self {
+            Expr::Num(n) => Ok(n.clone()),
+            Expr::Binary(op, left, right) => {
+                let (a, b) = (left.eval()?, right.eval()?);
+                let result = match op {
+                    BinOp::Add => a.checked_add(&b),
+                    BinOp::Sub => a.checked_sub(&b),
+                    BinOp::Mul => a.checked_mul(&b),
+                    BinOp::Div if b.is_zero() => return Err(EvalError::DivisionByZero),
+                    BinOp::Div => a.checked_div(&b),
+                    BinOp::Pow => b
+                        .to_usize()
+                        .and_then(|exponent| num::checked_pow(a, exponent)),
+                };
+                result.ok_or(EvalError::OutOfRange)
+            }
+        }
+    }
+
+    /// Parse infix text made of non-negative integers, binary operators `+ - * / ^` and
+    /// parentheses.
+    pub fn parse(s: &str, precedence: Precedence) -> Result<Self, ParseExprError> {
+        let tokens = tokenize(s)?;
+        let mut parser = Parser {
+            tokens: &tokens,
+            next: 0,
+            precedence,
+        };
+        let expr = parser.expression(0)?;
+        match parser.tokens.get(parser.next) {
+            None => Ok(expr),
+            Some((offset, _)) => Err(ParseExprError::UnexpectedToken(*offset)),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Token {
+    Number(String),
+    Op(BinOp),
+    Open,
+    Close,
+}
+
+/// Split the text into tokens along with their byte offsets.
+fn tokenize(s: &str) -> Result<Vec<(usize, Token)>, ParseExprError> {
+    let mut tokens: Vec<(usize, Token)> = vec![];
+    for (offset, c) in s.char_indices() {
+        let token = match c {
+            _ if c.is_whitespace() => continue,
+            '0'..='9' => {
+                if let Some((_, Token::Number(digits))) = tokens.last_mut()
+                    && s[..offset].ends_with(|c: char| c.is_ascii_digit())
+                {
+                    digits.push(c);
+                    continue;
+                }
+                Token::Number(c.to_string())
+            }
+            '(' => Token::Open,
+            ')' => Token::Close,
+            _ => Token::Op(
+                BinOp::try_from(c).map_err(|c| ParseExprError::UnexpectedCharacter(offset, c))?,
+            ),
+        };
+        tokens.push((offset, token));
+    }
+    Ok(tokens)
+}
+
+/// Precedence climbing parser over the tokens.
+struct Parser<'a> {
+    tokens: &'a [(usize, Token)],
+    next: usize,
+    precedence: Precedence,
+}
+
+impl Parser<'_> {
+    /// Parse an expression whose operators bind at least as tight as `min_binding`.
+    fn expression<T: Number>(&mut self, min_binding: u8) -> Result<Expr<T>, ParseExprError> {
+        let mut left = self.operand()?;
+        while let Some((_, Token::Op(op))) = self.tokens.get(self.next) {
+            let (binding, right_to_left) = self.precedence.binding(*op);
+            if binding < min_binding {
+                break;
+            }
+            self.next += 1;
+            let right = self.expression(if right_to_left { binding } else { binding + 1 })?;
+            left = Expr::binary(*op, left, right);
+        }
+        Ok(left)
+    }
+
+    /// Parse a number or a parenthesized expression.
+    fn operand<T: Number>(&mut self) -> Result<Expr<T>, ParseExprError> {
+        let (offset, token) = self
+            .tokens
+            .get(self.next)
+            .ok_or(ParseExprError::UnexpectedEnd)?;
+        self.next += 1;
+        match token {
+            Token::Number(digits) => digits
+                .parse()
+                .map(Expr::Num)
+                .map_err(|_| ParseExprError::InvalidNumber(digits.clone())),
+            Token::Open => {
+                let expr = self.expression(0)?;
+                match self.tokens.get(self.next) {
+                    Some((_, Token::Close)) => {
+                        self.next += 1;
+                        Ok(expr)
+                    }
+                    Some((offset, _)) => Err(ParseExprError::UnexpectedToken(*offset)),
+                    None => Err(ParseExprError::UnexpectedEnd),
+                }
+            }
+            _ => Err(ParseExprError::UnexpectedToken(*offset)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BinOp, EvalError, Expr, ParseExprError, Precedence};
+    use num::BigInt;
+
+    fn eval(s: &str, precedence: Precedence) -> Result<u64, EvalError> {
+        Expr::parse(s, precedence).unwrap().eval()
+    }
+
+    #[test]
+    fn precedences() {
+        let s = "1 + 2 * 3 + 4 * 5 + 6";
+        assert_eq!(eval(s, Precedence::LeftToRight), Ok(71));
+        assert_eq!(eval(s, Precedence::AddBeforeMul), Ok(231));
+        assert_eq!(eval(s, Precedence::Standard), Ok(33));
+
+        let s = "2 * 3 + (4 * 5)";
+        assert_eq!(eval(s, Precedence::LeftToRight), Ok(26));
+        assert_eq!(eval(s, Precedence::AddBeforeMul), Ok(46));
+
+        let s = "((2 + 4 * 9) * (6 + 9 * 8 + 6) + 6) + 2 + 4 * 2";
+        assert_eq!(eval(s, Precedence::LeftToRight), Ok(13632));
+        assert_eq!(eval(s, Precedence::AddBeforeMul), Ok(23340));
+
+        assert_eq!(eval("2 ^ 3 ^ 2", Precedence::Standard), Ok(512));
+        assert_eq!(eval("2 ^ 3 ^ 2", Precedence::LeftToRight), Ok(64));
+        assert_eq!(eval("100 - 10 - 1", Precedence::Standard), Ok(89));
+        assert_eq!(eval("100 / 10 / 2", Precedence::Standard), Ok(5));
+    }
+
+    #[test]
+    fn arithmetic() {
+        assert_eq!(
+            eval("1 - 2", Precedence::Standard),
+            Err(EvalError::OutOfRange)
+        );
+        assert_eq!(
+            eval("1 / (2 - 2)", Precedence::Standard),
+            Err(EvalError::DivisionByZero)
+        );
+        assert_eq!(
+            eval("2 ^ 64", Precedence::Standard),
+            Err(EvalError::OutOfRange)
+        );
+        let wide = Expr::<u128>::parse("2 ^ 64", Precedence::Standard).unwrap();
+        assert_eq!(wide.eval(), Ok(1 << 64));
+        let big = Expr::<BigInt>::parse("3 ^ 100 - 3 ^ 100 * 2", Precedence::Standard).unwrap();
+        assert_eq!(big.eval(), Ok(-BigInt::from(3).pow(100)));
+    }
+
+    #[test]
+    fn chains() {
+        let expr = Expr::chain(BinOp::Sub, [10u64, 3, 2]).unwrap();
+        assert_eq!(expr.eval(), Ok(5));
+        assert_eq!(
+            expr,
+            Expr::binary(
+                BinOp::Sub,
+                Expr::binary(BinOp::Sub, Expr::Num(10), Expr::Num(3)),
+                Expr::Num(2)
+            )
+        );
+        assert_eq!(Expr::chain(BinOp::Add, [7u64]), Some(Expr::Num(7)));
+        assert_eq!(Expr::<u64>::chain(BinOp::Add, []), None);
+    }
+
+    #[test]
+    fn parse_errors() {
+        let parse = |s| Expr::<u8>::parse(s, Precedence::Standard);
+        assert_eq!(
+            parse("1 + x"),
+            Err(ParseExprError::UnexpectedCharacter(4, 'x'))
+        );
+        assert_eq!(parse("1 + "), Err(ParseExprError::UnexpectedEnd));
+        assert_eq!(parse("(1 + 2"), Err(ParseExprError::UnexpectedEnd));
+        assert_eq!(parse("1 2"), Err(ParseExprError::UnexpectedToken(2)));
+        assert_eq!(parse("* 2"), Err(ParseExprError::UnexpectedToken(0)));
+        assert_eq!(parse("(1))"), Err(ParseExprError::UnexpectedToken(3)));
+        assert_eq!(
+            parse("256"),
+            Err(ParseExprError::InvalidNumber("256".to_string()))
+        );
+    }
+}
@@ -0,0 +1,288 @@
+//! Rectilinear polygons on the integer lattice.
+#![allow(dead_code)]
+use super::vector::{Vec2, Vector};
+
+/// Segment given by its two endpoints.
+pub type Segment = (Vec2, Vec2);
+
+/// Return the segment with its endpoints sorted: `(top-left, bottom-right)`.
+fn normalize(segment: &Segment) -> Segment {
+    (
+        segment.0.componentwise_min(&segment.1),
+        segment.0.componentwise_max(&segment.1),
+    )
+}
+
+/// Test whether two horizontal or vertical segments share at least one point.
+pub fn segments_intersect(s: &Segment, t: &Segment) -> bool {
+    // axis-aligned segments are degenerate rectangles, they meet iff their bounding boxes do.
+    let (s1, s2) = normalize(s);
+    let (t1, t2) = normalize(t);
+    s1.x() <= t2.x() && t1.x() <= s2.x() && s1.y() <= t2.y() && t1.y() <= s2.y()
+}
+
+/// A simple polygon whose edges are all horizontal or vertical.
+///
+/// The polygon is closed: the last vertex is joined to the first one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Polygon {
+    vertices: Vec<Vec2>,
+}
+
+impl Polygon {
+    /// Create a polygon from its vertices, in either clockwise or counterclockwise order.
+    ///
+    /// Panics if an edge is neither horizontal nor vertical.
+    pub fn new(vertices: Vec<Vec2>) -> Self {
+        let polygon = Polygon { vertices };
+        assert!(
+            polygon
+                .edges()
+                .all(|(a, b)| a.x() == b.x() || a.y() == b.y()),
+            "edges must be horizontal or vertical"
+        );
+        polygon
+    }
+
+    pub fn vertices(&self) -> &[Vec2] {
+        &self.vertices
+    }
+
+    /// Iterate over the edges, endpoints are normalized as `(top-left, bottom-right)`.
+    pub fn edges(&self) -> impl Iterator<Item = Segment> + '_ {
+        let next = self.vertices.iter().cycle().skip(1);
+        self.vertices
+            .iter()
+            .zip(next)
+            .map(|(a, b)| normalize(&(*a, *b)))
+    }
+
+    /// Return the area enclosed by the polygon, with the shoelace formula.
+    pub fn area(&self) -> i64 {
+        let next = self.vertices.iter().cycle().skip(1);
+        let twice_area: i64 = self
+            .vertices
+            .iter()
+            .zip(next)
+            .map(|(a, b)| a.x() * b.y() - b.x() * a.y())
+            .sum();
+        twice_area.abs() / 2
+    }
+
+    /// Return the length of the boundary, which is also the number of lattice points on it.
+    pub fn perimeter(&self) -> i64 {
+        self.edges().map(|(a, b)| a.manhattan_distance(&b)).sum()
+    }
+
+    /// Return the number of lattice points strictly inside the polygon, with Pick's theorem.
+    pub fn interior_points(&self) -> i64 {
+        self.area() - self.perimeter() / 2 + 1
+    }
+
+    /// Return the number of lattice points inside the polygon or on its boundary, that is the
+    /// number of tiles covered when vertices are the centers of tiles.
+    pub fn lattice_points(&self) -> i64 {
+        self.interior_points() + self.perimeter()
+    }
+
+    pub fn on_boundary(&self, p: &Vec2) -> bool {
+        self.edges()
+            .any(|edge| segments_intersect(&edge, &(*p, *p)))
+    }
+
+    /// Test whether the point is inside the polygon or on its boundary.
+    pub fn contains(&self, p: &Vec2) -> bool {
+        self.contains_doubled(&(*p * 2))
+    }
+
+    /// Same as `contains` for a point whose coordinates are doubled, so that points halfway
+    /// between lattice points can be tested.
+    fn contains_doubled(&self, p: &Vec2) -> bool {
+        let mut inside = false;
+        for (a, b) in self.edges() {
+            let (a, b) = (a * 2, b * 2);
+            if segments_intersect(&(a, b), &(*p, *p)) {
+                return true;
+            }
+            // cast a ray towards increasing x, vertical edges are half-open to count each
+            // vertex once.
+            if a.x() == b.x() && a.x() > p.x() && a.y() <= p.y() && p.y() < b.y() {
+                inside = !inside;
+            }
+        }
+        inside
+    }
+
+    /// Test whether some edge goes through the interior of the rectangle given by two opposite
+    /// corners.
+    pub fn edge_crosses_rectangle(&self, c1: &Vec2, c2: &Vec2) -> bool {
+        let (c1, c2) = normalize(&(*c1, *c2));
+        self.edges().any(|(a, b)| {
+            if a.x() == b.x() {
+                c1.x() < a.x() && a.x() < c2.x() && a.y().max(c1.y()) < b.y().min(c2.y())
+            } else {
+                c1.y() < a.y() && a.y() < c2.y() && a.x().max(c1.x()) < b.x().min(c2.x())
+            }
+        })
+    }
+
+    /// Test whether the rectangle given by two opposite corners lies inside the polygon, its
+    /// boundary included.
+    pub fn contains_rectangle(&self, c1: &Vec2, c2: &Vec2) -> bool {
+        let (c1, c2) = normalize(&(*c1, *c2));
+        if c1.x() == c2.x() || c1.y() == c2.y() {
+            return self.contains_segment(&(c1, c2));
+        }
+        // without edge crossing its interior, the rectangle is either fully inside or fully
+        // outside: test its center.
+        !self.edge_crosses_rectangle(&c1, &c2) && self.contains_doubled(&(c1 + c2))
+    }
+
+    /// Test whether a horizontal or vertical segment lies inside the polygon.
+    fn contains_segment(&self, segment: &Segment) -> bool {
+        let (s1, s2) = *segment;
+        let axis = if s1.x() == s2.x() { 1 } else { 0 };
+        // between two consecutive vertex coordinates, the segment cannot enter or leave the
+        // polygon: test these coordinates and the points halfway between them.
+        let mut stops = self
+            .vertices
+            .iter()
+            .map(|v| v[axis])
+            .filter(|c| s1[axis] < *c && *c < s2[axis])
+            .chain([s1[axis], s2[axis]])
+            .map(|c| 2 * c)
+            .collect::<Vec<_>>();
+        stops.sort();
+        stops.dedup();
+        let halfway = stops
+            .windows(2)
+            .map(|w| (w[0] + w[1]) / 2)
+            .collect::<Vec<_>>();
+        stops.into_iter().chain(halfway).all(|c| {
+            let mut p = s1 * 2;
+            p[axis] = c;
+            self.contains_doubled(&p)
+        })
+    }
+}
+
+impl FromIterator<Vec2> for Polygon {
+    fn from_iter<I: IntoIterator<Item = Vec2>>(iter: I) -> Self {
+        Polygon::new(iter.into_iter().collect())
+    }
+}
+
+/// Convenience constructor from `(x, y)` pairs.
+impl From<&[(i64, i64)]> for Polygon {
+    fn from(value: &[(i64, i64)]) -> Self {
+        value.iter().map(|(x, y)| Vector([*x, *y])).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Polygon, segments_intersect};
+    use crate::etc::vector::Vector;
+
+    /// The shape of the day09 example:
+    ///
+    /// ```text
+    /// ..............
+    /// .......#XXX#..
+    /// .......XXXXX..
+    /// ..#XXXX#XXXX..
+    /// ..XXXXXXXXXX..
+    /// ..#XXXXXX#XX..
+    /// .........XXX..
+    /// .........#X#..
+    /// ..............
+    /// ```
+    fn example() -> Polygon {
+        Polygon::from(
+            &[
+                (7, 1),
+                (11, 1),
+                (11, 7),
+                (9, 7),
+                (9, 5),
+                (2, 5),
+                (2, 3),
+                (7, 3),
+            ][..],
+        )
+    }
+
+    #[test]
+    #[should_panic]
+    fn not_rectilinear() {
+        let _ = Polygon::from(&[(0, 0), (2, 0), (1, 1)][..]);
+    }
+
+    #[test]
+    fn segments() {
+        let s = (Vector([0, 0]), Vector([4, 0]));
+        assert!(segments_intersect(&s, &(Vector([2, -1]), Vector([2, 3]))));
+        assert!(segments_intersect(&s, &(Vector([4, 0]), Vector([4, 3]))));
+        assert!(segments_intersect(&s, &(Vector([6, 0]), Vector([3, 0]))));
+        assert!(!segments_intersect(&s, &(Vector([5, -1]), Vector([5, 3]))));
+        assert!(!segments_intersect(&s, &(Vector([0, 1]), Vector([4, 1]))));
+    }
+
+    #[test]
+    fn area_and_points() {
+        let square = Polygon::from(&[(0, 0), (0, 2), (2, 2), (2, 0)][..]);
+        assert_eq!(square.area(), 4);
+        assert_eq!(square.perimeter(), 8);
+        assert_eq!(square.interior_points(), 1);
+        assert_eq!(square.lattice_points(), 9);
+
+        let shape = example();
+        assert_eq!(shape.area(), 30);
+        assert_eq!(shape.lattice_points(), 46);
+    }
+
+    #[test]
+    fn contains() {
+        let shape = example();
+        assert!(shape.contains(&Vector([7, 1])));
+        assert!(shape.contains(&Vector([8, 2])));
+        assert!(shape.contains(&Vector([2, 4])));
+        assert!(shape.contains(&Vector([10, 7])));
+        assert!(!shape.contains(&Vector([6, 2])));
+        assert!(!shape.contains(&Vector([8, 6])));
+        assert!(!shape.contains(&Vector([12, 4])));
+        assert!(shape.on_boundary(&Vector([9, 6])));
+        assert!(!shape.on_boundary(&Vector([10, 6])));
+    }
+
+    #[test]
+    fn contains_rectangle() {
+        let shape = example();
+        assert!(shape.contains_rectangle(&Vector([9, 5]), &Vector([2, 3])));
+        assert!(shape.contains_rectangle(&Vector([9, 7]), &Vector([11, 1])));
+        assert!(!shape.contains_rectangle(&Vector([2, 5]), &Vector([11, 1])));
+        assert!(!shape.contains_rectangle(&Vector([2, 3]), &Vector([7, 1])));
+        // degenerate rectangles
+        assert!(shape.contains_rectangle(&Vector([2, 4]), &Vector([11, 4])));
+        assert!(!shape.contains_rectangle(&Vector([2, 6]), &Vector([11, 6])));
+        assert!(shape.contains_rectangle(&Vector([9, 6]), &Vector([11, 6])));
+
+        // a U shape whose gap touches the rectangle only along its border
+        let u = Polygon::from(
+            &[
+                (0, 0),
+                (0, 4),
+                (6, 4),
+                (6, 0),
+                (4, 0),
+                (4, 2),
+                (2, 2),
+                (2, 0),
+            ][..],
+        );
+        assert!(u.contains_rectangle(&Vector([0, 2]), &Vector([6, 4])));
+        assert!(!u.contains_rectangle(&Vector([0, 1]), &Vector([6, 4])));
+        assert!(!u.contains_rectangle(&Vector([0, 0]), &Vector([6, 0])));
+        assert!(u.contains_rectangle(&Vector([2, 0]), &Vector([2, 4])));
+    }
+}
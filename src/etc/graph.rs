@@ -0,0 +1,259 @@
+//! Minimum spanning trees and clustering over weighted edge lists.
+#![allow(dead_code)]
+use partitions::PartitionVec;
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+/// An undirected edge between two nodes, identified by their index, and its weight.
+pub type Edge<W> = (usize, usize, W);
+
+/// When to stop adding edges to a forest.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Stop {
+    /// Go on until the candidate edges run out, which gives a minimum spanning forest.
+    Exhausted,
+    /// Stop once the given number of candidate edges were considered, whether they joined two
+    /// components or not.
+    AfterCandidates(usize),
+    /// Stop once the forest holds the given number of edges, redundant candidates not counting.
+    AfterEdges(usize),
+    /// Stop as soon as there are no more than the given number of components left.
+    Components(usize),
+}
+
+/// A spanning forest grown edge by edge, along with its components.
+#[derive(Debug, Clone)]
+pub struct Forest<W> {
+    partitions: PartitionVec<()>,
+    /// The edges of the forest, in the order they were added.
+    pub edges: Vec<Edge<W>>,
+    /// The number of candidate edges considered so far.
+    pub candidates: usize,
+}
+
+impl<W: Copy> Forest<W> {
+    /// Return a forest of isolated nodes.
+    pub fn new(nodes: usize) -> Self {
+        Forest {
+            partitions: vec![(); nodes].into(),
+            edges: vec![],
+            candidates: 0,
+        }
+    }
+
+    pub fn nodes(&self) -> usize {
+        self.partitions.len()
+    }
+
+    /// Consider a candidate edge, keep it if it joins two components.
+    pub fn add(&mut self, edge: Edge<W>) -> bool {
+        self.candidates += 1;
+        let (from, to, _) = edge;
+        if self.partitions.same_set(from, to) {
+            return false;
+        }
+        self.partitions.union(from, to);
+        self.edges.push(edge);
+        true
+    }
+
+    /// Consider the candidate edges in order until `stop`. The candidates which are not used are
+    /// left in the iterator, so that a later call can go on.
+    pub fn extend<I: Iterator<Item = Edge<W>>>(&mut self, candidates: &mut I, stop: Stop) {
+        while !self.should_stop(stop) {
+            let Some(edge) = candidates.next() else {
+                break;
+            };
+            self.add(edge);
+        }
+    }
+
+    fn should_stop(&self, stop: Stop) -> bool {
+        match stop {
+            Stop::Exhausted => false,
+            Stop::AfterCandidates(k) => self.candidates >= k,
+            Stop::AfterEdges(k) => self.edges.len() >= k,
+            Stop::Components(c) => self.components() <= c,
+        }
+    }
+
+    pub fn components(&self) -> usize {
+        self.partitions.amount_of_sets()
+    }
+
+    pub fn same_component(&self, a: usize, b: usize) -> bool {
+        self.partitions.same_set(a, b)
+    }
+
+    /// Return the sizes of the components, largest first.
+    pub fn component_sizes(&self) -> Vec<usize> {
+        let mut sizes = self
+            .partitions
+            .all_sets()
+            .map(|set| set.count())
+            .collect::<Vec<_>>();
+        sizes.sort_by(|a, b| b.cmp(a));
+        sizes
+    }
+
+    /// Return the last edge added, which joined the two last components of a spanning tree.
+    pub fn last_edge(&self) -> Option<&Edge<W>> {
+        self.edges.last()
+    }
+}
+
+impl<W: Copy + std::iter::Sum> Forest<W> {
+    pub fn total_weight(&self) -> W {
+        self.edges.iter().map(|(_, _, w)| *w).sum()
+    }
+}
+
+/// Run Kruskal's algorithm over candidate edges sorted by increasing weight, which may be
+/// produced lazily.
+pub fn kruskal<W: Copy, I: IntoIterator<Item = Edge<W>>>(
+    nodes: usize,
+    sorted_edges: I,
+    stop: Stop,
+) -> Forest<W> {
+    let mut forest = Forest::new(nodes);
+    forest.extend(&mut sorted_edges.into_iter(), stop);
+    forest
+}
+
+/// Run Kruskal's algorithm over edges in any order. Edges of equal weight keep their order.
+pub fn kruskal_unsorted<W: Copy + Ord>(
+    nodes: usize,
+    mut edges: Vec<Edge<W>>,
+    stop: Stop,
+) -> Forest<W> {
+    edges.sort_by_key(|(_, _, w)| *w);
+    kruskal(nodes, edges, stop)
+}
+
+/// Run Prim's algorithm, growing a tree from each node not reached yet, so that disconnected
+/// graphs give a minimum spanning forest. Edges are added in the order they are reached.
+pub fn prim<W: Copy + Ord>(nodes: usize, edges: &[Edge<W>]) -> Forest<W> {
+    let mut adjacency = vec![vec![]; nodes];
+    for (from, to, weight) in edges {
+        adjacency[*from].push((*to, *weight));
+        adjacency[*to].push((*from, *weight));
+    }
+
+    let mut forest = Forest::new(nodes);
+    let mut reached = vec![false; nodes];
+    for root in 0..nodes {
+        if reached[root] {
+            continue;
+        }
+        reached[root] = true;
+        let mut queue = BinaryHeap::new();
+        queue.extend(
+            adjacency[root]
+                .iter()
+                .map(|(to, w)| Reverse((*w, root, *to))),
+        );
+        while let Some(Reverse((weight, from, to))) = queue.pop() {
+            if reached[to] {
+                continue;
+            }
+            reached[to] = true;
+            forest.add((from, to, weight));
+            queue.extend(
+                adjacency[to]
+                    .iter()
+                    .map(|(next, w)| Reverse((*w, to, *next))),
+            );
+        }
+    }
+    forest
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Edge, Stop, kruskal, kruskal_unsorted, prim};
+    use crate::etc::random::XorShift;
+
+    /// Two triangles joined by a heavy edge, and an isolated node.
+    fn graph() -> Vec<Edge<u32>> {
+        vec![
+            (0, 1, 1),
+            (1, 2, 2),
+            (0, 2, 3),
+            (3, 4, 1),
+            (4, 5, 5),
+            (3, 5, 4),
+            (2, 3, 10),
+        ]
+    }
+
+    #[test]
+    fn spanning_forest() {
+        let forest = kruskal_unsorted(7, graph(), Stop::Exhausted);
+        assert_eq!(
+            forest.edges,
+            vec![(0, 1, 1), (3, 4, 1), (1, 2, 2), (3, 5, 4), (2, 3, 10)]
+        );
+        assert_eq!(forest.total_weight(), 18);
+        assert_eq!(forest.candidates, 7);
+        assert_eq!(forest.components(), 2);
+        assert_eq!(forest.component_sizes(), vec![6, 1]);
+        assert_eq!(forest.last_edge(), Some(&(2, 3, 10)));
+
+        let forest = prim(7, &graph());
+        assert_eq!(forest.total_weight(), 18);
+        assert_eq!(forest.edges[..2], [(0, 1, 1), (1, 2, 2)]);
+        assert_eq!(forest.component_sizes(), vec![6, 1]);
+    }
+
+    #[test]
+    fn stops() {
+        let mut edges = graph();
+        edges.sort_by_key(|(_, _, w)| *w);
+
+        // the fourth candidate (0, 2) is redundant: it counts but does not change the components.
+        let forest = kruskal(7, edges.clone(), Stop::AfterCandidates(4));
+        assert_eq!(forest.candidates, 4);
+        assert_eq!(forest.edges.len(), 3);
+        assert_eq!(forest.component_sizes(), vec![3, 2, 1, 1]);
+
+        let forest = kruskal(7, edges.clone(), Stop::AfterCandidates(5));
+        assert_eq!(forest.component_sizes(), vec![3, 3, 1]);
+
+        // while the fourth edge only comes with the fifth candidate.
+        let forest = kruskal(7, edges.clone(), Stop::AfterEdges(4));
+        assert_eq!(forest.candidates, 5);
+        assert_eq!(forest.last_edge(), Some(&(3, 5, 4)));
+        assert_eq!(forest.component_sizes(), vec![3, 3, 1]);
+
+        let forest = kruskal(7, edges.clone(), Stop::Components(3));
+        assert_eq!(forest.last_edge(), Some(&(3, 5, 4)));
+        assert!(!forest.same_component(2, 3));
+
+        // resuming from where the previous stop left the candidates.
+        let mut candidates = edges.into_iter();
+        let mut forest = kruskal(7, &mut candidates, Stop::AfterCandidates(2));
+        assert_eq!(forest.components(), 5);
+        forest.extend(&mut candidates, Stop::Components(2));
+        assert_eq!(forest.last_edge(), Some(&(2, 3, 10)));
+        assert_eq!(candidates.next(), None);
+    }
+
+    #[test]
+    fn kruskal_matches_prim() {
+        let mut rng = XorShift::new(5);
+        for _ in 0..50 {
+            let nodes = rng.range(1..=30) as usize;
+            let edges = (0..rng.range(0..=80))
+                .map(|_| {
+                    let from = rng.range(0..=nodes as i64 - 1) as usize;
+                    let to = rng.range(0..=nodes as i64 - 1) as usize;
+                    (from, to, rng.range(0..=20))
+                })
+                .collect::<Vec<_>>();
+            let by_kruskal = kruskal_unsorted(nodes, edges.clone(), Stop::Exhausted);
+            let by_prim = prim(nodes, &edges);
+            assert_eq!(by_kruskal.total_weight(), by_prim.total_weight());
+            assert_eq!(by_kruskal.component_sizes(), by_prim.component_sizes());
+        }
+    }
+}
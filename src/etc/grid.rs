@@ -46,6 +46,40 @@ impl Point {
     pub fn taxicab_distance(&self, other: &Self) -> u64 {
         self.0.abs_diff(other.0) + self.1.abs_diff(other.1)
     }
+
+    /// Return the Euclidean remainder of each coordinate, which is never negative unlike `%`.
+    pub fn rem_euclid(&self, rhs: &Self) -> Self {
+        Self(self.0.rem_euclid(rhs.0), self.1.rem_euclid(rhs.1))
+    }
+
+    /// Iterate over the points of the Bresenham line from this point to the other, both ends
+    /// included.
+    pub fn line_to(&self, other: &Self) -> impl Iterator<Item = Point> + use<> {
+        let end = *other;
+        let (step_line, step_column) = ((end.0 - self.0).signum(), (end.1 - self.1).signum());
+        let (lines, columns) = ((end.0 - self.0).abs(), (end.1 - self.1).abs());
+        let mut error = columns - lines;
+        let mut next = Some(*self);
+        std::iter::from_fn(move || {
+            let current = next?;
+            next = if current == end {
+                None
+            } else {
+                let mut pos = current;
+                let e2 = 2 * error;
+                if e2 >= -lines {
+                    error -= lines;
+                    pos.1 += step_column;
+                }
+                if e2 <= columns {
+                    error += columns;
+                    pos.0 += step_line;
+                }
+                Some(pos)
+            };
+            Some(current)
+        })
+    }
 }
 
 impl<T: std::fmt::Debug> std::fmt::Debug for Grid<T> {
@@ -134,6 +168,24 @@ pub const ALL_DIRECTIONS: [Point; 8] = [
     Point(-1, 1),
 ];
 
+/// Call `f` with the position and the content of each cell one of the `deltas` away from
+/// `origin`, `lookup` returning the actual position and content of the cell at a position, if
+/// there is one.
+///
+/// The kinds of grids share it to visit neighbours, whatever their boundaries.
+pub fn for_each_neighbour<'a, T, L, F>(origin: &Point, deltas: &[Point], lookup: L, mut f: F)
+where
+    T: 'a,
+    L: Fn(Point) -> Option<(Point, &'a T)>,
+    F: FnMut(Point, &T),
+{
+    for delta in deltas {
+        if let Some((pos, cell)) = lookup(*origin + *delta) {
+            f(pos, cell);
+        }
+    }
+}
+
 impl Grid<char> {
     /// Read a grid from the given string, lines are separated by ascii whitespace.
     ///
@@ -299,26 +351,205 @@ impl<T> Grid<T> {
         self.valid_position(&point).then_some(point)
     }
 
-    pub fn for_each_taxicab_neighbour<F>(&self, origin: &Point, mut f: F)
+    /// Iterate over the cells met when repeatedly stepping along `delta` from `origin` (excluded)
+    /// until the edge of the grid, along with their positions. A zero `delta` meets no cell.
+    ///
+    /// Use `take_while` to stop the ray at the first cell that fails a predicate.
+    pub fn ray<'a>(
+        &'a self,
+        origin: &Point,
+        delta: &Point,
+    ) -> impl Iterator<Item = (Point, &'a T)> + use<'a, T> {
+        let delta = *delta;
+        let mut pos = *origin;
+        std::iter::from_fn(move || {
+            if delta == Point(0, 0) {
+                return None;
+            }
+            pos = self.step(&pos, &delta)?;
+            Some((pos, self.unchecked_get(&pos)))
+        })
+    }
+
+    /// Return the first cell along `delta` from `origin` (excluded) that satisfies `predicate`.
+    pub fn first_visible<P>(
+        &self,
+        origin: &Point,
+        delta: &Point,
+        predicate: P,
+    ) -> Option<(Point, &T)>
+    where
+        P: Fn(&T) -> bool,
+    {
+        self.ray(origin, delta).find(|(_, item)| predicate(item))
+    }
+
+    /// Return the first cell satisfying `predicate` in each of the directions of `ALL_DIRECTIONS`.
+    pub fn first_visible_all<P>(&self, origin: &Point, predicate: P) -> [Option<(Point, &T)>; 8]
+    where
+        P: Fn(&T) -> bool,
+    {
+        ALL_DIRECTIONS.map(|delta| self.first_visible(origin, &delta, &predicate))
+    }
+
+    /// Iterate over the cells of the Bresenham line between two points, both ends included,
+    /// skipping the points outside the grid.
+    pub fn line<'a>(
+        &'a self,
+        from: &Point,
+        to: &Point,
+    ) -> impl Iterator<Item = (Point, &'a T)> + use<'a, T> {
+        from.line_to(to)
+            .filter_map(|pos| self.get(&pos).map(|item| (pos, item)))
+    }
+
+    pub fn for_each_taxicab_neighbour<F>(&self, origin: &Point, f: F)
     where
         F: FnMut(Point, &T),
     {
-        for delta in &TAXICAB_DIRECTIONS{
-            if let Some(pos) = self.step(origin, delta) {
-                f(pos, self.unchecked_get(&pos));
-            }
-        }
+        for_each_neighbour(origin, &TAXICAB_DIRECTIONS, |pos| self.cell(pos), f);
     }
 
-    pub fn for_each_tchebychev_neighbour<F>(&self, origin: &Point, mut f: F)
+    pub fn for_each_tchebychev_neighbour<F>(&self, origin: &Point, f: F)
     where
         F: FnMut(Point, &T),
     {
-        for delta in &ALL_DIRECTIONS {
-            if let Some(pos) = self.step(origin, delta) {
-                f(pos, self.unchecked_get(&pos));
-            }
-        }
+        for_each_neighbour(origin, &ALL_DIRECTIONS, |pos| self.cell(pos), f);
+    }
+
+    fn cell(&self, pos: Point) -> Option<(Point, &T)> {
+        self.get(&pos).map(|item| (pos, item))
+    }
+
+    /// Return the cells of the given line, from left to right.
+    pub fn row(&self, line: usize) -> &[T] {
+        &self.items[line * self.columns..(line + 1) * self.columns]
+    }
+
+    pub fn row_mut(&mut self, line: usize) -> &mut [T] {
+        &mut self.items[line * self.columns..(line + 1) * self.columns]
+    }
+
+    /// Iterate over the lines of the grid, from top to bottom.
+    pub fn rows(&self) -> std::slice::ChunksExact<'_, T> {
+        self.items.chunks_exact(self.columns)
+    }
+
+    pub fn rows_mut(&mut self) -> std::slice::ChunksExactMut<'_, T> {
+        self.items.chunks_exact_mut(self.columns)
+    }
+
+    /// Iterate over the cells of the given column, from top to bottom.
+    pub fn column(&self, column: usize) -> impl Iterator<Item = &T> {
+        assert!(column < self.columns, "invalid column");
+        self.items[column..].iter().step_by(self.columns)
+    }
+
+    pub fn column_mut(&mut self, column: usize) -> impl Iterator<Item = &mut T> {
+        assert!(column < self.columns, "invalid column");
+        self.items[column..].iter_mut().step_by(self.columns)
+    }
+
+    /// Iterate over the columns of the grid, from left to right.
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        (0..self.columns).map(move |column| self.column(column))
+    }
+
+    /// Return the index of `origin` and the number of cells met when stepping along `delta`
+    /// (either south-east or south-west) until the edge of the grid.
+    fn diagonal_span(&self, origin: &Point, delta: Point) -> (usize, usize) {
+        let start = self.strict_index(origin);
+        let below = self.lines - origin.0 as usize;
+        let len = if delta == Point::SOUTH_EAST {
+            below.min(self.columns - origin.1 as usize)
+        } else {
+            below.min(origin.1 as usize + 1)
+        };
+        (start, len)
+    }
+
+    /// Iterate over the cells of the diagonal going south-east from `origin` to the edge of the
+    /// grid, along with their positions.
+    ///
+    /// Panics if `origin` is outside the grid.
+    pub fn diagonal<'a>(
+        &'a self,
+        origin: &Point,
+    ) -> impl Iterator<Item = (Point, &'a T)> + use<'a, T> {
+        let origin = *origin;
+        let (start, len) = self.diagonal_span(&origin, Point::SOUTH_EAST);
+        self.items[start..]
+            .iter()
+            .step_by(self.columns + 1)
+            .take(len)
+            .enumerate()
+            .map(move |(i, item)| (origin + Point::SOUTH_EAST * (i as i64), item))
+    }
+
+    pub fn diagonal_mut<'a>(
+        &'a mut self,
+        origin: &Point,
+    ) -> impl Iterator<Item = (Point, &'a mut T)> + use<'a, T> {
+        let origin = *origin;
+        let (start, len) = self.diagonal_span(&origin, Point::SOUTH_EAST);
+        self.items[start..]
+            .iter_mut()
+            .step_by(self.columns + 1)
+            .take(len)
+            .enumerate()
+            .map(move |(i, item)| (origin + Point::SOUTH_EAST * (i as i64), item))
+    }
+
+    /// Iterate over the cells of the anti-diagonal going south-west from `origin` to the edge of
+    /// the grid, along with their positions.
+    ///
+    /// Panics if `origin` is outside the grid.
+    pub fn anti_diagonal<'a>(
+        &'a self,
+        origin: &Point,
+    ) -> impl Iterator<Item = (Point, &'a T)> + use<'a, T> {
+        let origin = *origin;
+        let (start, len) = self.diagonal_span(&origin, Point::SOUTH_WEST);
+        self.items[start..]
+            .iter()
+            .step_by((self.columns - 1).max(1))
+            .take(len)
+            .enumerate()
+            .map(move |(i, item)| (origin + Point::SOUTH_WEST * (i as i64), item))
+    }
+
+    pub fn anti_diagonal_mut<'a>(
+        &'a mut self,
+        origin: &Point,
+    ) -> impl Iterator<Item = (Point, &'a mut T)> + use<'a, T> {
+        let origin = *origin;
+        let (start, len) = self.diagonal_span(&origin, Point::SOUTH_WEST);
+        self.items[start..]
+            .iter_mut()
+            .step_by((self.columns - 1).max(1))
+            .take(len)
+            .enumerate()
+            .map(move |(i, item)| (origin + Point::SOUTH_WEST * (i as i64), item))
+    }
+
+    /// Iterate over all the diagonals of the grid, from the bottom-left-most one to the
+    /// top-right-most one.
+    pub fn diagonals(&self) -> impl Iterator<Item = impl Iterator<Item = (Point, &T)>> {
+        let starts = (0..self.lines)
+            .rev()
+            .map(|line| Point(line as i64, 0))
+            .chain((1..self.columns).map(|column| Point(0, column as i64)));
+        starts.map(move |start| self.diagonal(&start))
+    }
+
+    /// Iterate over all the anti-diagonals of the grid, from the top-left-most one to the
+    /// bottom-right-most one.
+    pub fn anti_diagonals(&self) -> impl Iterator<Item = impl Iterator<Item = (Point, &T)>> {
+        let last_column = self.columns as i64 - 1;
+        let starts = (0..self.columns)
+            .map(|column| Point(0, column as i64))
+            .chain((1..self.lines).map(move |line| Point(line as i64, last_column)));
+        starts.map(move |start| self.anti_diagonal(&start))
     }
 }
 
@@ -414,6 +645,230 @@ where
     }
 }
 
+/// A view of a grid where both axes wrap around, as if the grid was tiled infinitely.
+///
+/// Any point is valid and maps to the cell at the Euclidean remainder of its coordinates.
+pub struct WrappingGrid<'a, T> {
+    grid: &'a Grid<T>,
+}
+
+impl<T> Grid<T> {
+    /// Return a view of the grid where positions wrap around both axes.
+    pub fn wrapping(&self) -> WrappingGrid<'_, T> {
+        WrappingGrid { grid: self }
+    }
+}
+
+impl<'a, T> WrappingGrid<'a, T> {
+    /// Return the position inside the underlying grid that `pos` maps to.
+    pub fn wrap(&self, pos: &Point) -> Point {
+        pos.rem_euclid(&Point(self.grid.lines as i64, self.grid.columns as i64))
+    }
+
+    /// Retrieve value at given point.
+    pub fn get(&self, pos: &Point) -> &'a T {
+        self.grid.unchecked_get(&self.wrap(pos))
+    }
+
+    /// Apply `delta` to `origin`, wrapping around the grid's edges.
+    pub fn step(&self, origin: &Point, delta: &Point) -> Point {
+        self.wrap(&origin.add(*delta))
+    }
+
+    pub fn for_each_taxicab_neighbour<F>(&self, origin: &Point, f: F)
+    where
+        F: FnMut(Point, &T),
+    {
+        for_each_neighbour(origin, &TAXICAB_DIRECTIONS, |pos| self.cell(pos), f);
+    }
+
+    pub fn for_each_tchebychev_neighbour<F>(&self, origin: &Point, f: F)
+    where
+        F: FnMut(Point, &T),
+    {
+        for_each_neighbour(origin, &ALL_DIRECTIONS, |pos| self.cell(pos), f);
+    }
+
+    fn cell(&self, pos: Point) -> Option<(Point, &'a T)> {
+        let pos = self.wrap(&pos);
+        Some((pos, self.grid.unchecked_get(&pos)))
+    }
+}
+
+impl<T> Grid<T>
+where
+    T: Clone,
+{
+    /// Extract the sub-grid of the given size whose top-left-most item is at `top_left`.
+    ///
+    /// Return `None` if the sub-grid does not fit inside the grid's boundaries.
+    pub fn subgrid(&self, top_left: &Point, lines: usize, columns: usize) -> Option<Grid<T>> {
+        let bottom_right = *top_left + Point(lines as i64 - 1, columns as i64 - 1);
+        if lines == 0
+            || columns == 0
+            || !self.valid_position(top_left)
+            || !self.valid_position(&bottom_right)
+        {
+            return None;
+        }
+        let (line, column) = (top_left.0 as usize, top_left.1 as usize);
+        let items = (line..line + lines)
+            .flat_map(|l| self.row(l)[column..column + columns].iter().cloned())
+            .collect();
+        Some(Grid {
+            lines,
+            columns,
+            items,
+        })
+    }
+
+    /// Iterate over all the `lines` × `columns` sub-grids, along with the position of their
+    /// top-left-most item, in reading order.
+    pub fn windows(&self, lines: usize, columns: usize) -> impl Iterator<Item = (Point, Grid<T>)> {
+        let last_line = (self.lines + 1).saturating_sub(lines);
+        let last_column = (self.columns + 1).saturating_sub(columns);
+        (0..last_line)
+            .flat_map(move |l| (0..last_column).map(move |c| Point(l as i64, c as i64)))
+            .filter_map(move |pos| Some((pos, self.subgrid(&pos, lines, columns)?)))
+    }
+
+    /// Return a copy of the grid rotated by a quarter turn clockwise.
+    pub fn rotate_clockwise(&self) -> Grid<T> {
+        let items = (0..self.columns)
+            .flat_map(|c| {
+                (0..self.lines)
+                    .rev()
+                    .map(move |l| self.items[l * self.columns + c].clone())
+            })
+            .collect();
+        Grid {
+            lines: self.columns,
+            columns: self.lines,
+            items,
+        }
+    }
+
+    /// Return a copy of the grid mirrored along its vertical axis.
+    pub fn flip_horizontal(&self) -> Grid<T> {
+        let items = self
+            .rows()
+            .flat_map(|row| row.iter().rev().cloned())
+            .collect();
+        Grid {
+            lines: self.lines,
+            columns: self.columns,
+            items,
+        }
+    }
+
+    /// Return a copy of the grid mirrored along its main diagonal.
+    pub fn transpose(&self) -> Grid<T> {
+        let items = self.columns().flatten().cloned().collect();
+        Grid {
+            lines: self.columns,
+            columns: self.lines,
+            items,
+        }
+    }
+
+    /// Return the 8 orientations of the grid: the 4 rotations of the grid followed by the 4
+    /// rotations of its mirror image.
+    ///
+    /// Orientations are not deduplicated, a symmetric grid yields identical orientations.
+    pub fn orientations(&self) -> [Grid<T>; 8] {
+        let r0 = self.clone();
+        let r1 = r0.rotate_clockwise();
+        let r2 = r1.rotate_clockwise();
+        let r3 = r2.rotate_clockwise();
+        let f0 = self.flip_horizontal();
+        let f1 = f0.rotate_clockwise();
+        let f2 = f1.rotate_clockwise();
+        let f3 = f2.rotate_clockwise();
+        [r0, r1, r2, r3, f0, f1, f2, f3]
+    }
+}
+
+impl<T> Grid<T> {
+    /// Test whether `pattern` placed with its top-left-most item at `top_left` matches this grid.
+    ///
+    /// `matches` is called with each pattern cell and the grid cell below it, which allows
+    /// wildcards in the pattern. An empty pattern never matches.
+    pub fn matches_at<P, F>(&self, top_left: &Point, pattern: &Grid<P>, matches: F) -> bool
+    where
+        F: Fn(&P, &T) -> bool,
+    {
+        let bottom_right = *top_left + Point(pattern.lines as i64 - 1, pattern.columns as i64 - 1);
+        if pattern.lines == 0
+            || pattern.columns == 0
+            || !self.valid_position(top_left)
+            || !self.valid_position(&bottom_right)
+        {
+            return false;
+        }
+        let (line, column) = (top_left.0 as usize, top_left.1 as usize);
+        pattern.rows().enumerate().all(|(l, pattern_row)| {
+            let row = &self.row(line + l)[column..column + pattern.columns];
+            pattern_row.iter().zip(row).all(|(p, v)| matches(p, v))
+        })
+    }
+
+    /// Return the positions of the top-left-most item of all the occurrences of `pattern`, where
+    /// cells are compared with `matches`.
+    pub fn find_pattern_by<P, F>(&self, pattern: &Grid<P>, matches: F) -> Vec<Point>
+    where
+        F: Fn(&P, &T) -> bool,
+    {
+        let mut found = vec![];
+        for line in 0..(self.lines + 1).saturating_sub(pattern.lines) {
+            for column in 0..(self.columns + 1).saturating_sub(pattern.columns) {
+                let pos = Point(line as i64, column as i64);
+                if self.matches_at(&pos, pattern, &matches) {
+                    found.push(pos);
+                }
+            }
+        }
+        found
+    }
+
+    /// Same as `find_pattern_by` but the pattern is tried in its 8 orientations.
+    ///
+    /// Return the position of each occurrence along with the index of the matching orientation
+    /// as given by `Grid::orientations`.
+    pub fn find_pattern_oriented_by<P, F>(
+        &self,
+        pattern: &Grid<P>,
+        matches: F,
+    ) -> Vec<(Point, usize)>
+    where
+        P: Clone,
+        F: Fn(&P, &T) -> bool,
+    {
+        if pattern.lines == 0 || pattern.columns == 0 {
+            return vec![];
+        }
+        pattern
+            .orientations()
+            .iter()
+            .enumerate()
+            .flat_map(|(orientation, oriented)| {
+                self.find_pattern_by(oriented, &matches)
+                    .into_iter()
+                    .map(move |pos| (pos, orientation))
+            })
+            .collect()
+    }
+}
+
+impl<T> Grid<T>
+where
+    T: PartialEq,
+{
+    /// Return the positions of the top-left-most item of all the exact occurrences of `pattern`.
+    pub fn find_pattern(&self, pattern: &Grid<T>) -> Vec<Point> {
+        self.find_pattern_by(pattern, |p, v| p == v)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::{Grid, Point};
@@ -507,4 +962,242 @@ mod tests {
         assert_eq!(None, g.position(|v| *v == '0'));
         assert_eq!(Some(Point(1, 3)), g.position(|v| *v == '8'));
     }
+
+    #[test]
+    fn rows_and_columns() {
+        let mut g = Grid::new("123\n456\n");
+        assert_eq!(g.row(1), &['4', '5', '6']);
+        assert_eq!(g.rows().count(), 2);
+        assert_eq!(
+            g.columns()
+                .map(|column| column.collect::<String>())
+                .collect::<Vec<_>>(),
+            vec!["14", "25", "36"]
+        );
+        g.column_mut(2).for_each(|v| *v = '0');
+        g.row_mut(0)[0] = '9';
+        assert_eq!(g.items.iter().collect::<String>(), "920450");
+    }
+
+    #[test]
+    fn diagonals() {
+        let g = Grid::new("123\n456\n");
+        assert_eq!(
+            g.diagonal(&Point(0, 1)).collect::<Vec<_>>(),
+            vec![(Point(0, 1), &'2'), (Point(1, 2), &'6')]
+        );
+        assert_eq!(
+            g.diagonals()
+                .map(|d| d.map(|(_, v)| v).collect::<String>())
+                .collect::<Vec<_>>(),
+            vec!["4", "15", "26", "3"]
+        );
+    }
+
+    #[test]
+    fn anti_diagonals() {
+        let mut g = Grid::new("123\n456\n");
+        assert_eq!(
+            g.anti_diagonal(&Point(0, 2)).collect::<Vec<_>>(),
+            vec![(Point(0, 2), &'3'), (Point(1, 1), &'5')]
+        );
+        assert_eq!(
+            g.anti_diagonals()
+                .map(|d| d.map(|(_, v)| v).collect::<String>())
+                .collect::<Vec<_>>(),
+            vec!["1", "24", "35", "6"]
+        );
+        g.anti_diagonal_mut(&Point(0, 1))
+            .for_each(|(_, v)| *v = '0');
+        g.diagonal_mut(&Point(0, 1)).for_each(|(_, v)| *v = '9');
+        assert_eq!(g.items.iter().collect::<String>(), "193059");
+    }
+
+    #[test]
+    fn single_column_anti_diagonals() {
+        let g = Grid::new("1\n2\n3\n");
+        assert_eq!(
+            g.anti_diagonals()
+                .map(|d| d.map(|(_, v)| v).collect::<String>())
+                .collect::<Vec<_>>(),
+            vec!["1", "2", "3"]
+        );
+    }
+
+    #[test]
+    fn subgrid() {
+        let g = Grid::new("1234\n5678\n9abc\n");
+        let s = g.subgrid(&Point(1, 1), 2, 2).unwrap();
+        assert_eq!((s.lines, s.columns), (2, 2));
+        assert_eq!(s.items.iter().collect::<String>(), "67ab");
+        assert!(g.subgrid(&Point(1, 1), 3, 2).is_none());
+        assert!(g.subgrid(&Point(0, 3), 1, 2).is_none());
+    }
+
+    #[test]
+    fn windows() {
+        let g = Grid::new("1234\n5678\n9abc\n");
+        let windows = g.windows(2, 3).collect::<Vec<_>>();
+        assert_eq!(windows.len(), 4);
+        assert_eq!(windows[3].0, Point(1, 1));
+        assert_eq!(windows[3].1.items.iter().collect::<String>(), "678abc");
+        assert_eq!(g.windows(4, 1).count(), 0);
+    }
+
+    #[test]
+    fn orientations() {
+        let g = Grid::new("12\n34\n56\n");
+        let r = g.rotate_clockwise();
+        assert_eq!((r.lines, r.columns), (2, 3));
+        assert_eq!(r.items.iter().collect::<String>(), "531642");
+        assert_eq!(
+            g.flip_horizontal().items.iter().collect::<String>(),
+            "214365"
+        );
+        assert_eq!(g.transpose().items.iter().collect::<String>(), "135246");
+        let all = g.orientations();
+        assert_eq!(all[2].items.iter().collect::<String>(), "654321");
+        assert_eq!(all[7].items, g.transpose().items);
+    }
+
+    #[test]
+    fn find_pattern() {
+        let g = Grid::new("#.#.\n####\n.##.\n");
+        let pattern = Grid::new("#.\n##\n");
+        assert_eq!(g.find_pattern(&pattern), vec![Point(0, 0), Point(0, 2)]);
+
+        // '.' in the pattern matches anything
+        let wildcard = |p: &char, v: &char| *p == '.' || p == v;
+        assert_eq!(
+            g.find_pattern_by(&pattern, wildcard),
+            vec![Point(0, 0), Point(0, 2), Point(1, 1)]
+        );
+
+        let l_shape = Grid::new("#.\n#.\n##\n");
+        assert!(g.find_pattern_by(&l_shape, wildcard).is_empty());
+
+        // an empty pattern never matches
+        let empty = Grid {
+            lines: 0,
+            columns: 0,
+            items: vec![],
+        };
+        assert!(Grid::new("12\n34\n").find_pattern(&empty).is_empty());
+        assert!(!g.matches_at(&Point(0, 0), &empty, wildcard));
+        let no_columns = Grid {
+            lines: 2,
+            columns: 0,
+            items: vec![],
+        };
+        assert!(g.find_pattern_oriented_by(&no_columns, wildcard).is_empty());
+        assert_eq!(
+            g.find_pattern_oriented_by(&l_shape, wildcard),
+            vec![
+                (Point(1, 1), 1),
+                (Point(0, 0), 3),
+                (Point(0, 1), 4),
+                (Point(0, 0), 5),
+                (Point(1, 0), 7)
+            ]
+        );
+    }
+
+    #[test]
+    fn rem_euclid() {
+        assert_eq!(Point(-1, 7).rem_euclid(&Point(3, 5)), Point(2, 2));
+        assert_eq!(Point(-1, 7) % Point(3, 5), Point(-1, 2));
+    }
+
+    #[test]
+    fn wrapping() {
+        let g = Grid::new("123\n456\n");
+        let w = g.wrapping();
+        assert_eq!(w.get(&Point(-1, -1)), &'6');
+        assert_eq!(w.get(&Point(4, 7)), &'2');
+        assert_eq!(w.step(&Point(0, 2), &Point::EAST), Point(0, 0));
+        assert_eq!(w.step(&Point(0, 0), &(Point::NORTH * 3)), Point(1, 0));
+
+        let mut neighbours = vec![];
+        w.for_each_taxicab_neighbour(&Point(0, 0), |pos, v| neighbours.push((pos, *v)));
+        assert_eq!(
+            neighbours,
+            vec![
+                (Point(0, 1), '2'),
+                (Point(1, 0), '4'),
+                (Point(0, 2), '3'),
+                (Point(1, 0), '4')
+            ]
+        );
+        let mut count = 0;
+        w.for_each_tchebychev_neighbour(&Point(1, 1), |_, _| count += 1);
+        assert_eq!(count, 8);
+    }
+
+    #[test]
+    fn line_to() {
+        assert_eq!(
+            Point(0, 0).line_to(&Point(1, 3)).collect::<Vec<_>>(),
+            vec![Point(0, 0), Point(0, 1), Point(1, 2), Point(1, 3)]
+        );
+        assert_eq!(
+            Point(2, 2).line_to(&Point(-1, -1)).collect::<Vec<_>>(),
+            vec![Point(2, 2), Point(1, 1), Point(0, 0), Point(-1, -1)]
+        );
+        assert_eq!(
+            Point(3, 1).line_to(&Point(0, 1)).collect::<Vec<_>>(),
+            vec![Point(3, 1), Point(2, 1), Point(1, 1), Point(0, 1)]
+        );
+        assert_eq!(
+            Point(4, 4).line_to(&Point(4, 4)).collect::<Vec<_>>(),
+            vec![Point(4, 4)]
+        );
+        let line = Point(0, 0).line_to(&Point(-7, 3)).collect::<Vec<_>>();
+        assert_eq!(line.len(), 8);
+        assert!(
+            line.windows(2)
+                .all(|w| w[0].0 - w[1].0 == 1 && (w[1].1 - w[0].1) <= 1)
+        );
+    }
+
+    #[test]
+    fn ray() {
+        let g = Grid::new("1234\n5678\n9abc\n");
+        assert_eq!(
+            g.ray(&Point(0, 1), &Point::SOUTH).collect::<Vec<_>>(),
+            vec![(Point(1, 1), &'6'), (Point(2, 1), &'a')]
+        );
+        assert_eq!(g.ray(&Point(0, 0), &Point::NORTH).count(), 0);
+        assert_eq!(
+            g.ray(&Point(0, 0), &Point::EAST)
+                .take_while(|(_, v)| **v != '4')
+                .count(),
+            2
+        );
+        assert_eq!(g.ray(&Point(1, 1), &Point(0, 0)).count(), 0);
+    }
+
+    #[test]
+    fn first_visible() {
+        let g = Grid::new("#.#.\n....\n#..#\n");
+        assert_eq!(
+            g.first_visible(&Point(2, 0), &Point::EAST, |v| *v == '#'),
+            Some((Point(2, 3), &'#'))
+        );
+        let seen = g.first_visible_all(&Point(1, 1), |v| *v == '#');
+        assert_eq!(seen.iter().flatten().count(), 3);
+        assert_eq!(seen[6], None);
+        assert_eq!(seen[5], Some((Point(0, 0), &'#')));
+        assert_eq!(g.first_visible(&Point(0, 0), &Point(0, 0), |_| true), None);
+    }
+
+    #[test]
+    fn line() {
+        let g = Grid::new("123\n456\n789\n");
+        assert_eq!(
+            g.line(&Point(-1, -1), &Point(2, 2))
+                .map(|(_, v)| v)
+                .collect::<String>(),
+            "159"
+        );
+    }
 }
@@ -0,0 +1,310 @@
+//! Dense 3D grids of voxels.
+#![allow(dead_code)]
+use super::grid::Grid;
+use super::search::flood_fill;
+use super::vector::{Vec3, Vector};
+
+/// The 6 directions towards the voxels sharing a face.
+pub const FACE_DIRECTIONS: [Vec3; 6] = [
+    Vector([1, 0, 0]),
+    Vector([-1, 0, 0]),
+    Vector([0, 1, 0]),
+    Vector([0, -1, 0]),
+    Vector([0, 0, 1]),
+    Vector([0, 0, -1]),
+];
+
+/// Return the 26 directions towards the voxels sharing a face, an edge or a corner.
+pub fn all_directions() -> [Vec3; 26] {
+    let mut directions = [Vec3::zero(); 26];
+    let deltas = (-1..=1)
+        .flat_map(|x| (-1..=1).flat_map(move |y| (-1..=1).map(move |z| Vector([x, y, z]))))
+        .filter(|d| *d != Vec3::zero());
+    for (direction, delta) in directions.iter_mut().zip(deltas) {
+        *direction = delta;
+    }
+    directions
+}
+
+/// A 3D grid indexed by `(x, y, z)` positions, stored `x` first then `y` then `z`.
+///
+/// The origin `(0,0,0)` is the first item.
+#[derive(Clone, PartialEq, Eq)]
+pub struct Grid3<T> {
+    pub width: usize,
+    pub height: usize,
+    pub depth: usize,
+    pub items: Vec<T>,
+}
+
+impl<T> Grid3<T> {
+    pub fn valid_position(&self, pos: &Vec3) -> bool {
+        (0..self.width as i64).contains(&pos.x())
+            && (0..self.height as i64).contains(&pos.y())
+            && (0..self.depth as i64).contains(&pos.z())
+    }
+
+    pub fn checked_index(&self, pos: &Vec3) -> Option<usize> {
+        self.valid_position(pos).then(|| {
+            (pos.x() as usize)
+                + self.width * ((pos.y() as usize) + self.height * (pos.z() as usize))
+        })
+    }
+
+    /// Return the position of the given index.
+    ///
+    /// Panics if the index is not valid.
+    pub fn strict_position(&self, index: usize) -> Vec3 {
+        assert!(index < self.items.len(), "index must be valid");
+        let x = index % self.width;
+        let y = (index / self.width) % self.height;
+        let z = index / (self.width * self.height);
+        Vector([x as i64, y as i64, z as i64])
+    }
+
+    pub fn get(&self, pos: &Vec3) -> Option<&T> {
+        self.checked_index(pos).map(|index| &self.items[index])
+    }
+
+    pub fn get_mut(&mut self, pos: &Vec3) -> Option<&mut T> {
+        self.checked_index(pos).map(|index| &mut self.items[index])
+    }
+
+    /// Set the value at the given position, return the previous one or `None` if the position is
+    /// outside the grid.
+    pub fn set(&mut self, pos: &Vec3, v: T) -> Option<T> {
+        self.get_mut(pos).map(|cell| std::mem::replace(cell, v))
+    }
+
+    pub fn for_each_with_position<F>(&self, mut f: F)
+    where
+        F: FnMut(Vec3, &T),
+    {
+        for (index, v) in self.items.iter().enumerate() {
+            f(self.strict_position(index), v);
+        }
+    }
+
+    /// Call `f` with each voxel sharing a face with `origin`.
+    pub fn for_each_face_neighbour<F>(&self, origin: &Vec3, mut f: F)
+    where
+        F: FnMut(Vec3, &T),
+    {
+        for delta in FACE_DIRECTIONS {
+            let pos = *origin + delta;
+            if let Some(v) = self.get(&pos) {
+                f(pos, v);
+            }
+        }
+    }
+
+    /// Call `f` with each of the (up to 26) voxels touching `origin`.
+    pub fn for_each_neighbour<F>(&self, origin: &Vec3, mut f: F)
+    where
+        F: FnMut(Vec3, &T),
+    {
+        for delta in all_directions() {
+            let pos = *origin + delta;
+            if let Some(v) = self.get(&pos) {
+                f(pos, v);
+            }
+        }
+    }
+
+    /// Return the number of faces between a voxel satisfying `solid` and a voxel that does not,
+    /// the outside of the grid not being solid. Faces around internal cavities are counted.
+    pub fn surface_area<P>(&self, solid: P) -> usize
+    where
+        P: Fn(&T) -> bool,
+    {
+        let mut area = 0;
+        self.for_each_with_position(|pos, v| {
+            if solid(v) {
+                area += FACE_DIRECTIONS
+                    .iter()
+                    .filter(|delta| !self.get(&(pos + **delta)).is_some_and(&solid))
+                    .count();
+            }
+        });
+        area
+    }
+
+    /// Same as `surface_area` but only counting the faces that can be reached from outside the
+    /// grid, moving through voxels that are not `solid`.
+    pub fn exterior_surface_area<P>(&self, solid: P) -> usize
+    where
+        P: Fn(&T) -> bool,
+    {
+        // the grid is surrounded by a layer of empty voxels, in which the flood fill starts.
+        let padded = |pos: &Vec3| {
+            (-1..=self.width as i64).contains(&pos.x())
+                && (-1..=self.height as i64).contains(&pos.y())
+                && (-1..=self.depth as i64).contains(&pos.z())
+        };
+        let is_solid = |pos: &Vec3| self.get(pos).is_some_and(&solid);
+        let outside = flood_fill(Vector([-1, -1, -1]), |pos| {
+            FACE_DIRECTIONS
+                .map(|delta| *pos + delta)
+                .into_iter()
+                .filter(|next| padded(next) && !is_solid(next))
+                .collect::<Vec<_>>()
+        });
+        outside
+            .iter()
+            .map(|pos| {
+                FACE_DIRECTIONS
+                    .iter()
+                    .filter(|delta| is_solid(&(*pos + **delta)))
+                    .count()
+            })
+            .sum()
+    }
+}
+
+impl<T> Grid3<T>
+where
+    T: Default + Clone,
+{
+    pub fn default(width: usize, height: usize, depth: usize) -> Self {
+        Grid3 {
+            width,
+            height,
+            depth,
+            items: vec![T::default(); width * height * depth],
+        }
+    }
+}
+
+impl<T> Grid3<T>
+where
+    T: Clone,
+{
+    /// Return the layer at the given `z` as a 2D grid, lines being `y` and columns `x`.
+    pub fn layer(&self, z: usize) -> Grid<T> {
+        let size = self.width * self.height;
+        Grid {
+            lines: self.height,
+            columns: self.width,
+            items: self.items[z * size..(z + 1) * size].to_vec(),
+        }
+    }
+
+    /// Iterate over the layers, by increasing `z`.
+    pub fn layers(&self) -> impl Iterator<Item = Grid<T>> + '_ {
+        (0..self.depth).map(|z| self.layer(z))
+    }
+}
+
+impl Grid3<bool> {
+    /// Create the smallest grid containing all the points, and return it along with the real
+    /// position of its origin. The voxels at the given points are `true`.
+    ///
+    /// Without points, the grid is empty and its origin is zero.
+    pub fn from_points(points: &[Vec3]) -> (Self, Vec3) {
+        if points.is_empty() {
+            return (Grid3::default(0, 0, 0), Vec3::zero());
+        }
+        let min = points.iter().fold(points[0], |m, p| m.componentwise_min(p));
+        let max = points.iter().fold(points[0], |m, p| m.componentwise_max(p));
+        let size = (max - min).map(|d| d as usize + 1);
+        let mut grid = Grid3::default(size.x(), size.y(), size.z());
+        for p in points {
+            grid.set(&(*p - min), true);
+        }
+        (grid, min)
+    }
+}
+
+/// Display the layers one after the other, separated by a blank line.
+impl<T: std::fmt::Debug> std::fmt::Debug for Grid3<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for z in 0..self.depth {
+            if z > 0 {
+                f.write_str("\n")?;
+            }
+            writeln!(f, "z={z}")?;
+            for y in 0..self.height {
+                for x in 0..self.width {
+                    let index = x + self.width * (y + self.height * z);
+                    self.items[index].fmt(f)?;
+                }
+                f.write_str("\n")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Grid3, all_directions};
+    use crate::etc::vector::{Vec3, Vector};
+
+    fn droplet() -> Vec<Vec3> {
+        "2,2,2 1,2,2 3,2,2 2,1,2 2,3,2 2,2,1 2,2,3 2,2,4 2,2,6 1,2,5 3,2,5 2,1,5 2,3,5"
+            .split(' ')
+            .map(|p| p.parse().unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn positions() {
+        let mut grid = Grid3::<u8>::default(2, 3, 4);
+        assert_eq!(grid.items.len(), 24);
+        assert_eq!(grid.checked_index(&Vector([1, 2, 3])), Some(23));
+        assert_eq!(grid.strict_position(23), Vector([1, 2, 3]));
+        assert_eq!(grid.strict_position(7), Vector([1, 0, 1]));
+        assert_eq!(grid.checked_index(&Vector([2, 0, 0])), None);
+        assert_eq!(grid.set(&Vector([1, 0, 1]), 5), Some(0));
+        assert_eq!(grid.items[7], 5);
+        assert_eq!(grid.set(&Vector([0, -1, 0]), 5), None);
+    }
+
+    #[test]
+    fn neighbours() {
+        assert_eq!(all_directions().len(), 26);
+        let grid = Grid3::<u8>::default(3, 3, 3);
+        let mut count = 0;
+        grid.for_each_neighbour(&Vector([1, 1, 1]), |_, _| count += 1);
+        assert_eq!(count, 26);
+        count = 0;
+        grid.for_each_neighbour(&Vector([0, 0, 0]), |_, _| count += 1);
+        assert_eq!(count, 7);
+        count = 0;
+        grid.for_each_face_neighbour(&Vector([0, 1, 2]), |_, _| count += 1);
+        assert_eq!(count, 4);
+    }
+
+    #[test]
+    fn surface() {
+        let (grid, origin) = Grid3::from_points(&[Vector([1, 1, 1]), Vector([2, 1, 1])]);
+        assert_eq!(origin, Vector([1, 1, 1]));
+        assert_eq!(grid.surface_area(|v| *v), 10);
+        assert_eq!(grid.exterior_surface_area(|v| *v), 10);
+
+        let (grid, origin) = Grid3::from_points(&droplet());
+        assert_eq!(origin, Vector([1, 1, 1]));
+        assert_eq!((grid.width, grid.height, grid.depth), (3, 3, 6));
+        assert_eq!(grid.surface_area(|v| *v), 64);
+        assert_eq!(grid.exterior_surface_area(|v| *v), 58);
+
+        let (grid, origin) = Grid3::from_points(&[]);
+        assert_eq!(origin, Vector([0, 0, 0]));
+        assert_eq!((grid.width, grid.height, grid.depth), (0, 0, 0));
+        assert_eq!(grid.surface_area(|v| *v), 0);
+        assert_eq!(grid.exterior_surface_area(|v| *v), 0);
+    }
+
+    #[test]
+    fn layers() {
+        let (grid, _) = Grid3::from_points(&droplet());
+        let layer = grid.layer(4);
+        assert_eq!((layer.lines, layer.columns), (3, 3));
+        assert_eq!(
+            layer.items,
+            vec![false, true, false, true, false, true, false, true, false]
+        );
+        assert_eq!(grid.layers().count(), 6);
+        assert!(format!("{grid:?}").starts_with("z=0\nfalsefalsefalse\n"));
+    }
+}
@@ -0,0 +1,312 @@
+//! Hexagonal grids with axial coordinates.
+//!
+//! Hexagons are flat-topped, so that each one has a neighbour to the north and one to the south.
+//! In cube coordinates `(q, r, s)` with `q + r + s = 0`, north is `(0, -1, 1)` and north-east is
+//! `(1, -1, 0)`.
+#![allow(dead_code)]
+use super::search::{bfs_distances, flood_fill};
+use std::collections::{HashMap, HashSet};
+use std::str::FromStr;
+
+/// Position of a hexagon in axial coordinates, the third cube coordinate being `s = -q - r`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Hex {
+    pub q: i64,
+    pub r: i64,
+}
+
+impl Hex {
+    pub const ORIGIN: Hex = Hex { q: 0, r: 0 };
+
+    pub fn new(q: i64, r: i64) -> Self {
+        Hex { q, r }
+    }
+
+    /// Return the third cube coordinate.
+    pub fn s(&self) -> i64 {
+        -self.q - self.r
+    }
+
+    /// Return the number of steps to the other hexagon.
+    pub fn distance(&self, other: &Self) -> u64 {
+        let d = *self - *other;
+        (d.q.unsigned_abs() + d.r.unsigned_abs() + d.s().unsigned_abs()) / 2
+    }
+
+    pub fn neighbour(&self, direction: HexDirection) -> Self {
+        *self + direction.delta()
+    }
+
+    /// Return the 6 neighbours, clockwise from north.
+    pub fn neighbours(&self) -> [Hex; 6] {
+        HexDirection::ALL.map(|d| self.neighbour(d))
+    }
+
+    /// Rotate by 60° clockwise around the origin.
+    pub fn rotate_clockwise(&self) -> Self {
+        Hex::new(-self.r, -self.s())
+    }
+
+    /// Rotate by 60° counterclockwise around the origin.
+    pub fn rotate_counterclockwise(&self) -> Self {
+        Hex::new(-self.s(), -self.q)
+    }
+
+    /// Return the position reached by following the steps from this one.
+    pub fn walk<I: IntoIterator<Item = HexDirection>>(&self, steps: I) -> Self {
+        steps.into_iter().fold(*self, |pos, d| pos.neighbour(d))
+    }
+}
+
+impl std::ops::Add for Hex {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Hex::new(self.q + other.q, self.r + other.r)
+    }
+}
+
+impl std::ops::Sub for Hex {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        Hex::new(self.q - other.q, self.r - other.r)
+    }
+}
+
+impl std::ops::Mul<i64> for Hex {
+    type Output = Self;
+
+    fn mul(self, rhs: i64) -> Self {
+        Hex::new(self.q * rhs, self.r * rhs)
+    }
+}
+
+/// One of the 6 directions between flat-topped hexagons.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum HexDirection {
+    North,
+    NorthEast,
+    SouthEast,
+    South,
+    SouthWest,
+    NorthWest,
+}
+
+use HexDirection::*;
+
+impl HexDirection {
+    /// The 6 directions, clockwise from north.
+    pub const ALL: [HexDirection; 6] = [North, NorthEast, SouthEast, South, SouthWest, NorthWest];
+
+    /// Rotate clockwise by `sixths` sixths of a turn.
+    pub fn rotate(self, sixths: usize) -> Self {
+        Self::ALL[(self as usize + sixths) % 6]
+    }
+
+    pub fn opposite(self) -> Self {
+        self.rotate(3)
+    }
+
+    /// Return the offset of a single step in this direction.
+    pub fn delta(self) -> Hex {
+        match self {
+            North => Hex::new(0, -1),
+            NorthEast => Hex::new(1, -1),
+            SouthEast => Hex::new(1, 0),
+            South => Hex::new(0, 1),
+            SouthWest => Hex::new(-1, 1),
+            NorthWest => Hex::new(-1, 0),
+        }
+    }
+}
+
+/// Read a direction written as `n`, `ne`, `se`, `s`, `sw` or `nw`. The text is given back when it
+/// is not a direction.
+impl FromStr for HexDirection {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "n" => Ok(North),
+            "ne" => Ok(NorthEast),
+            "se" => Ok(SouthEast),
+            "s" => Ok(South),
+            "sw" => Ok(SouthWest),
+            "nw" => Ok(NorthWest),
+            _ => Err(s.to_string()),
+        }
+    }
+}
+
+/// Parse comma separated directions like `"ne,ne,s"`.
+pub fn parse_path(s: &str) -> Result<Vec<HexDirection>, String> {
+    s.trim().split(',').map(|step| step.parse()).collect()
+}
+
+/// A hexagonal grid, only the hexagons that were inserted belong to it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HexGrid<T> {
+    cells: HashMap<Hex, T>,
+}
+
+impl<T> Default for HexGrid<T> {
+    fn default() -> Self {
+        HexGrid {
+            cells: HashMap::new(),
+        }
+    }
+}
+
+impl<T> HexGrid<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    pub fn contains(&self, pos: &Hex) -> bool {
+        self.cells.contains_key(pos)
+    }
+
+    pub fn get(&self, pos: &Hex) -> Option<&T> {
+        self.cells.get(pos)
+    }
+
+    pub fn get_mut(&mut self, pos: &Hex) -> Option<&mut T> {
+        self.cells.get_mut(pos)
+    }
+
+    /// Set the value of a hexagon, returning its previous value.
+    pub fn insert(&mut self, pos: Hex, v: T) -> Option<T> {
+        self.cells.insert(pos, v)
+    }
+
+    pub fn remove(&mut self, pos: &Hex) -> Option<T> {
+        self.cells.remove(pos)
+    }
+
+    /// Iterate over the hexagons in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item = (&Hex, &T)> {
+        self.cells.iter()
+    }
+
+    /// Call `f` with each neighbour of `origin` that belongs to the grid.
+    pub fn for_each_neighbour<F>(&self, origin: &Hex, mut f: F)
+    where
+        F: FnMut(Hex, &T),
+    {
+        for pos in origin.neighbours() {
+            if let Some(v) = self.cells.get(&pos) {
+                f(pos, v);
+            }
+        }
+    }
+
+    /// Return the hexagons reachable from `start` through the hexagons satisfying `passable`.
+    /// `start` itself is always included.
+    pub fn flood_fill<P>(&self, start: &Hex, passable: P) -> HashSet<Hex>
+    where
+        P: Fn(&T) -> bool,
+    {
+        flood_fill(*start, |pos| self.passable_neighbours(pos, &passable))
+    }
+
+    /// Return the number of steps from `start` to every hexagon reachable through the hexagons
+    /// satisfying `passable`.
+    pub fn distances<P>(&self, start: &Hex, passable: P) -> HashMap<Hex, usize>
+    where
+        P: Fn(&T) -> bool,
+    {
+        bfs_distances(*start, |pos| self.passable_neighbours(pos, &passable))
+    }
+
+    fn passable_neighbours<P>(&self, origin: &Hex, passable: &P) -> Vec<Hex>
+    where
+        P: Fn(&T) -> bool,
+    {
+        let mut neighbours = Vec::with_capacity(6);
+        self.for_each_neighbour(origin, |pos, v| {
+            if passable(v) {
+                neighbours.push(pos);
+            }
+        });
+        neighbours
+    }
+}
+
+impl<T: Clone> HexGrid<T> {
+    /// Create the hexagon-shaped grid of all the hexagons within `radius` steps of the origin.
+    pub fn hexagon(radius: i64, v: T) -> Self {
+        let mut grid = HexGrid::new();
+        for q in -radius..=radius {
+            for r in (-radius).max(-q - radius)..=radius.min(-q + radius) {
+                grid.insert(Hex::new(q, r), v.clone());
+            }
+        }
+        grid
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Hex, HexDirection, HexGrid, parse_path};
+
+    fn distance_walked(path: &str) -> u64 {
+        Hex::ORIGIN
+            .walk(parse_path(path).unwrap())
+            .distance(&Hex::ORIGIN)
+    }
+
+    #[test]
+    fn distances() {
+        assert_eq!(distance_walked("ne,ne,ne"), 3);
+        assert_eq!(distance_walked("ne,ne,sw,sw"), 0);
+        assert_eq!(distance_walked("ne,ne,s,s"), 2);
+        assert_eq!(distance_walked("se,sw,se,sw,sw"), 3);
+        assert_eq!(parse_path("n,x"), Err("x".to_string()));
+    }
+
+    #[test]
+    fn directions() {
+        for d in HexDirection::ALL {
+            let delta = d.delta();
+            assert_eq!(delta.distance(&Hex::ORIGIN), 1);
+            assert_eq!(delta.rotate_clockwise(), d.rotate(1).delta());
+            assert_eq!(delta.rotate_counterclockwise(), d.rotate(5).delta());
+            assert_eq!(delta + d.opposite().delta(), Hex::ORIGIN);
+        }
+        let h = Hex::new(3, -1);
+        let mut rotated = h;
+        for _ in 0..6 {
+            rotated = rotated.rotate_clockwise();
+            assert_eq!(rotated.distance(&Hex::ORIGIN), 3);
+        }
+        assert_eq!(rotated, h);
+    }
+
+    #[test]
+    fn grids() {
+        let mut grid = HexGrid::hexagon(2, true);
+        assert_eq!(grid.len(), 19);
+        assert!(grid.iter().all(|(h, _)| h.distance(&Hex::ORIGIN) <= 2));
+
+        // wall off the origin except towards the south.
+        for d in HexDirection::ALL
+            .into_iter()
+            .filter(|d| *d != HexDirection::South)
+        {
+            grid.insert(d.delta(), false);
+        }
+        let distances = grid.distances(&Hex::ORIGIN, |open| *open);
+        assert_eq!(distances.len(), 14);
+        assert_eq!(distances[&Hex::new(0, -2)], 7);
+        assert_eq!(grid.flood_fill(&Hex::new(0, 1), |open| *open).len(), 14);
+    }
+}
@@ -0,0 +1,495 @@
+//! Image export of grids: PPM and PNG still images, animated GIF and numbered frames.
+#![allow(dead_code)]
+use super::grid::Grid;
+use std::collections::HashMap;
+use std::io::{self, Write};
+use std::path::Path;
+
+/// A colour as its red, green and blue components.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Rgb(pub u8, pub u8, pub u8);
+
+impl Rgb {
+    pub const BLACK: Rgb = Rgb(0, 0, 0);
+    pub const WHITE: Rgb = Rgb(255, 255, 255);
+    pub const RED: Rgb = Rgb(255, 0, 0);
+    pub const GREEN: Rgb = Rgb(0, 255, 0);
+    pub const BLUE: Rgb = Rgb(0, 0, 255);
+}
+
+/// An RGB image, pixels are stored line by line from the top-left-most one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Image {
+    pub width: usize,
+    pub height: usize,
+    pub pixels: Vec<Rgb>,
+}
+
+impl<T> Grid<T> {
+    /// Render the grid as an image where each cell is a `scale` × `scale` square of the colour
+    /// given by `colour`.
+    pub fn to_image<F>(&self, scale: usize, colour: F) -> Image
+    where
+        F: Fn(&T) -> Rgb,
+    {
+        let colours = self.items.iter().map(colour).collect::<Vec<_>>();
+        let width = self.columns * scale;
+        let height = self.lines * scale;
+        let pixels = (0..height)
+            .flat_map(|y| (0..width).map(move |x| (y / scale) * self.columns + x / scale))
+            .map(|index| colours[index])
+            .collect();
+        Image {
+            width,
+            height,
+            pixels,
+        }
+    }
+}
+
+impl Image {
+    /// Write the image in the binary PPM format (`P6`).
+    pub fn write_ppm<W: Write>(&self, out: &mut W) -> io::Result<()> {
+        write!(out, "P6\n{} {}\n255\n", self.width, self.height)?;
+        let bytes = self
+            .pixels
+            .iter()
+            .flat_map(|Rgb(r, g, b)| [*r, *g, *b])
+            .collect::<Vec<_>>();
+        out.write_all(&bytes)
+    }
+
+    /// Write the image in the PNG format, as 8-bit RGB without compression.
+    pub fn write_png<W: Write>(&self, out: &mut W) -> io::Result<()> {
+        out.write_all(b"\x89PNG\r\n\x1a\n")?;
+
+        let mut header = vec![];
+        header.extend((self.width as u32).to_be_bytes());
+        header.extend((self.height as u32).to_be_bytes());
+        // bit depth 8, colour type RGB, default compression, filter and no interlace
+        header.extend([8, 2, 0, 0, 0]);
+        write_png_chunk(out, b"IHDR", &header)?;
+
+        // each scanline starts with filter type 0 (none)
+        let mut raw = Vec::with_capacity(self.height * (1 + 3 * self.width));
+        for line in self.pixels.chunks(self.width.max(1)) {
+            raw.push(0);
+            raw.extend(line.iter().flat_map(|Rgb(r, g, b)| [*r, *g, *b]));
+        }
+        write_png_chunk(out, b"IDAT", &zlib_stored(&raw))?;
+        write_png_chunk(out, b"IEND", &[])
+    }
+
+    pub fn save_ppm<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        self.write_ppm(&mut io::BufWriter::new(std::fs::File::create(path)?))
+    }
+
+    pub fn save_png<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        self.write_png(&mut io::BufWriter::new(std::fs::File::create(path)?))
+    }
+}
+
+fn write_png_chunk<W: Write>(out: &mut W, kind: &[u8; 4], data: &[u8]) -> io::Result<()> {
+    out.write_all(&(data.len() as u32).to_be_bytes())?;
+    out.write_all(kind)?;
+    out.write_all(data)?;
+    let crc = crc32(kind.iter().chain(data));
+    out.write_all(&crc.to_be_bytes())
+}
+
+/// CRC-32 as used by PNG (reflected polynomial `0xEDB88320`).
+fn crc32<'a, I: IntoIterator<Item = &'a u8>>(bytes: I) -> u32 {
+    !bytes.into_iter().fold(!0u32, |crc, byte| {
+        (0..8).fold(crc ^ u32::from(*byte), |crc, _| {
+            if crc & 1 != 0 {
+                (crc >> 1) ^ 0xEDB8_8320
+            } else {
+                crc >> 1
+            }
+        })
+    })
+}
+
+fn adler32(bytes: &[u8]) -> u32 {
+    let (a, b) = bytes.iter().fold((1u32, 0u32), |(a, b), byte| {
+        let a = (a + u32::from(*byte)) % 65521;
+        (a, (b + a) % 65521)
+    });
+    (b << 16) | a
+}
+
+/// Wrap bytes in a zlib stream made of uncompressed deflate blocks.
+fn zlib_stored(raw: &[u8]) -> Vec<u8> {
+    let mut stream = vec![0x78, 0x01];
+    let blocks = raw.chunks(0xFFFF).collect::<Vec<_>>();
+    if blocks.is_empty() {
+        stream.extend([1, 0, 0, 0xFF, 0xFF]);
+    }
+    for (i, block) in blocks.iter().enumerate() {
+        let last = i + 1 == blocks.len();
+        let len = block.len() as u16;
+        stream.push(u8::from(last));
+        stream.extend(len.to_le_bytes());
+        stream.extend((!len).to_le_bytes());
+        stream.extend_from_slice(block);
+    }
+    stream.extend(adler32(raw).to_be_bytes());
+    stream
+}
+
+/// A sequence of images of the same size, for instance each generation of a simulation.
+#[derive(Debug, Clone, Default)]
+pub struct Frames {
+    pub frames: Vec<Image>,
+}
+
+impl Frames {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    pub fn push(&mut self, frame: Image) {
+        if let Some(first) = self.frames.first() {
+            assert_eq!(
+                (first.width, first.height),
+                (frame.width, frame.height),
+                "frames must have the same size"
+            );
+        }
+        self.frames.push(frame);
+    }
+
+    /// Save each frame as a PNG file named `{prefix}{index:04}.png` in the given directory.
+    pub fn save_numbered<P: AsRef<Path>>(&self, directory: P, prefix: &str) -> io::Result<()> {
+        for (index, frame) in self.frames.iter().enumerate() {
+            frame.save_png(directory.as_ref().join(format!("{prefix}{index:04}.png")))?;
+        }
+        Ok(())
+    }
+
+    pub fn save_gif<P: AsRef<Path>>(&self, path: P, delay: u16) -> io::Result<()> {
+        self.write_gif(&mut io::BufWriter::new(std::fs::File::create(path)?), delay)
+    }
+
+    /// Write the frames as a looping animated GIF, showing each frame for `delay` hundredths of
+    /// a second.
+    ///
+    /// All the frames share a single palette, so they may use at most 256 distinct colours.
+    pub fn write_gif<W: Write>(&self, out: &mut W, delay: u16) -> io::Result<()> {
+        if self.frames.is_empty() {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "no frame"));
+        }
+        let sizes = self
+            .frames
+            .iter()
+            .map(gif_size)
+            .collect::<io::Result<Vec<_>>>()?;
+
+        let mut palette: Vec<Rgb> = vec![];
+        let mut palette_index: HashMap<Rgb, u8> = HashMap::new();
+        for pixel in self.frames.iter().flat_map(|frame| &frame.pixels) {
+            if !palette_index.contains_key(pixel) {
+                if palette.len() == 256 {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidInput,
+                        "more than 256 colours",
+                    ));
+                }
+                palette_index.insert(*pixel, palette.len() as u8);
+                palette.push(*pixel);
+            }
+        }
+        // the palette size must be a power of two, at least 4 for the LZW minimum code size
+        let depth = (palette.len().max(4) - 1).ilog2() + 1;
+        palette.resize(1 << depth, Rgb::BLACK);
+
+        out.write_all(b"GIF89a")?;
+        out.write_all(&sizes[0])?;
+        // global colour table of 2^depth entries, no background colour, square pixels
+        out.write_all(&[0x80 | 0x70 | (depth as u8 - 1), 0, 0])?;
+        for Rgb(r, g, b) in &palette {
+            out.write_all(&[*r, *g, *b])?;
+        }
+        // loop forever
+        out.write_all(b"\x21\xFF\x0BNETSCAPE2.0\x03\x01\x00\x00\x00")?;
+
+        for (frame, size) in self.frames.iter().zip(&sizes) {
+            // graphic control extension with the frame delay
+            out.write_all(&[0x21, 0xF9, 0x04, 0x00])?;
+            out.write_all(&delay.to_le_bytes())?;
+            out.write_all(&[0x00, 0x00])?;
+            // image descriptor covering the whole canvas
+            out.write_all(&[0x2C, 0, 0, 0, 0])?;
+            out.write_all(size)?;
+            out.write_all(&[0])?;
+
+            let indices = frame
+                .pixels
+                .iter()
+                .map(|pixel| palette_index[pixel])
+                .collect::<Vec<_>>();
+            out.write_all(&[depth as u8])?;
+            for block in lzw_encode(&indices, depth).chunks(255) {
+                out.write_all(&[block.len() as u8])?;
+                out.write_all(block)?;
+            }
+            out.write_all(&[0])?;
+        }
+        out.write_all(&[0x3B])
+    }
+}
+
+/// Return the width and height of the image as GIF stores them, 16 bits each, little-endian.
+fn gif_size(image: &Image) -> io::Result<[u8; 4]> {
+    let dimension = |d: usize| {
+        u16::try_from(d).map_err(|_| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "image larger than 65535 pixels",
+            )
+        })
+    };
+    let [w0, w1] = dimension(image.width)?.to_le_bytes();
+    let [h0, h1] = dimension(image.height)?.to_le_bytes();
+    Ok([w0, w1, h0, h1])
+}
+
+/// Accumulate codes of variable width, least significant bit first.
+#[derive(Default)]
+struct BitWriter {
+    bytes: Vec<u8>,
+    buffer: u32,
+    bits: u32,
+}
+
+impl BitWriter {
+    fn write(&mut self, code: u16, width: u32) {
+        self.buffer |= u32::from(code) << self.bits;
+        self.bits += width;
+        while self.bits >= 8 {
+            self.bytes.push(self.buffer as u8);
+            self.buffer >>= 8;
+            self.bits -= 8;
+        }
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        if self.bits > 0 {
+            self.bytes.push(self.buffer as u8);
+        }
+        self.bytes
+    }
+}
+
+/// GIF flavour of LZW compression, codes grow from `min_code_size + 1` bits up to 12 bits.
+fn lzw_encode(indices: &[u8], min_code_size: u32) -> Vec<u8> {
+    let clear: u16 = 1 << min_code_size;
+    let end = clear + 1;
+    let mut writer = BitWriter::default();
+    let mut table: HashMap<(u16, u8), u16> = HashMap::new();
+    let mut next_code = end + 1;
+    let mut width = min_code_size + 1;
+
+    writer.write(clear, width);
+    let Some((&first, rest)) = indices.split_first() else {
+        writer.write(end, width);
+        return writer.finish();
+    };
+    let mut prefix = u16::from(first);
+    for &index in rest {
+        if let Some(&code) = table.get(&(prefix, index)) {
+            prefix = code;
+            continue;
+        }
+        writer.write(prefix, width);
+        if next_code < 4096 {
+            table.insert((prefix, index), next_code);
+            next_code += 1;
+            if next_code > (1 << width) && width < 12 {
+                width += 1;
+            }
+        } else {
+            writer.write(clear, width);
+            table.clear();
+            next_code = end + 1;
+            width = min_code_size + 1;
+        }
+        prefix = u16::from(index);
+    }
+    writer.write(prefix, width);
+    writer.write(end, width);
+    writer.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn checkerboard() -> Image {
+        Grid::new("#.\n.#\n").to_image(2, |c| if *c == '#' { Rgb::BLACK } else { Rgb::WHITE })
+    }
+
+    #[test]
+    fn grid_to_image() {
+        let image = checkerboard();
+        assert_eq!((image.width, image.height), (4, 4));
+        assert_eq!(image.pixels[0], Rgb::BLACK);
+        assert_eq!(image.pixels[2], Rgb::WHITE);
+        assert_eq!(image.pixels[5], Rgb::BLACK);
+        assert_eq!(image.pixels[10], Rgb::BLACK);
+        assert_eq!(image.pixels[12], Rgb::WHITE);
+    }
+
+    #[test]
+    fn ppm() {
+        let image = Grid::new("ab\n").to_image(1, |c| if *c == 'a' { Rgb::RED } else { Rgb::BLUE });
+        let mut out = vec![];
+        image.write_ppm(&mut out).unwrap();
+        assert_eq!(out, b"P6\n2 1\n255\n\xFF\x00\x00\x00\x00\xFF");
+    }
+
+    #[test]
+    fn checksums() {
+        assert_eq!(crc32(b"IEND"), 0xAE42_6082);
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+        assert_eq!(adler32(b"Wikipedia"), 0x11E6_0398);
+    }
+
+    #[test]
+    fn png() {
+        let image = checkerboard();
+        let mut out = vec![];
+        image.write_png(&mut out).unwrap();
+        assert_eq!(&out[..8], b"\x89PNG\r\n\x1a\n");
+        assert_eq!(&out[12..16], b"IHDR");
+        assert_eq!(&out[16..24], &[0, 0, 0, 4, 0, 0, 0, 4]);
+        assert_eq!(&out[out.len() - 12..], b"\0\0\0\0IEND\xAE\x42\x60\x82");
+
+        // the IDAT chunk holds a single stored deflate block with the raw scanlines
+        let idat_len = u32::from_be_bytes(out[33..37].try_into().unwrap()) as usize;
+        assert_eq!(&out[37..41], b"IDAT");
+        let zlib = &out[41..41 + idat_len];
+        let block_len = u16::from_le_bytes([zlib[3], zlib[4]]) as usize;
+        assert_eq!(zlib[2], 1);
+        assert_eq!(block_len, 4 * (1 + 4 * 3));
+        let raw = &zlib[7..7 + block_len];
+        assert_eq!(&raw[..7], &[0, 0, 0, 0, 0, 0, 0]);
+        assert_eq!(&raw[7..10], &[255, 255, 255]);
+    }
+
+    /// GIF flavour of LZW decompression.
+    fn lzw_decode(bytes: &[u8], min_code_size: u32) -> Vec<u8> {
+        let clear = 1usize << min_code_size;
+        let end = clear + 1;
+        let (mut buffer, mut bits) = (0u32, 0u32);
+        let mut bytes = bytes.iter();
+        let mut width = min_code_size + 1;
+        let mut table: Vec<Vec<u8>> = vec![];
+        let mut previous: Option<Vec<u8>> = None;
+        let mut output = vec![];
+        loop {
+            while bits < width {
+                buffer |= u32::from(*bytes.next().unwrap()) << bits;
+                bits += 8;
+            }
+            let code = (buffer & ((1 << width) - 1)) as usize;
+            buffer >>= width;
+            bits -= width;
+
+            if code == clear {
+                table = (0..clear).map(|i| vec![i as u8]).collect();
+                table.extend([vec![], vec![]]);
+                width = min_code_size + 1;
+                previous = None;
+                continue;
+            }
+            if code == end {
+                return output;
+            }
+            let entry = match (table.get(code), &previous) {
+                (Some(entry), _) => entry.clone(),
+                (None, Some(prev)) => [prev.clone(), vec![prev[0]]].concat(),
+                (None, None) => unreachable!(),
+            };
+            if let Some(prev) = previous
+                && table.len() < 4096
+            {
+                table.push([prev, vec![entry[0]]].concat());
+                if table.len() == 1 << width && width < 12 {
+                    width += 1;
+                }
+            }
+            output.extend(&entry);
+            previous = Some(entry);
+        }
+    }
+
+    #[test]
+    fn lzw_roundtrip() {
+        let mut seed = 12345u32;
+        let mut random = || {
+            seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12345);
+            (seed >> 16) as u8
+        };
+        // enough data to fill the code table several times
+        let indices = (0..50_000)
+            .map(|i| if i % 7 == 0 { random() % 4 } else { 1 })
+            .collect::<Vec<_>>();
+        assert_eq!(lzw_decode(&lzw_encode(&indices, 2), 2), indices);
+
+        let indices = (0..20_000).map(|_| random()).collect::<Vec<_>>();
+        assert_eq!(lzw_decode(&lzw_encode(&indices, 8), 8), indices);
+
+        assert_eq!(lzw_decode(&lzw_encode(&[], 2), 2), Vec::<u8>::new());
+    }
+
+    #[test]
+    fn gif() {
+        let mut frames = Frames::new();
+        frames.push(checkerboard());
+        frames.push(Grid::new("..\n..\n").to_image(2, |_| Rgb::WHITE));
+        let mut out = vec![];
+        frames.write_gif(&mut out, 10).unwrap();
+        assert_eq!(&out[..6], b"GIF89a");
+        assert_eq!(&out[6..10], &[4, 0, 4, 0]);
+        // 4 colours in the global colour table
+        assert_eq!(out[10], 0xF1);
+        assert_eq!(&out[13..16], &[0, 0, 0]);
+        assert_eq!(&out[16..19], &[255, 255, 255]);
+        assert_eq!(out.last(), Some(&0x3B));
+        assert_eq!(out.iter().filter(|b| **b == 0x2C).count(), 2);
+
+        let too_many = Image {
+            width: 300,
+            height: 1,
+            pixels: (0..300).map(|i| Rgb(i as u8, (i / 256) as u8, 0)).collect(),
+        };
+        let mut frames = Frames::new();
+        frames.push(too_many);
+        assert!(frames.write_gif(&mut vec![], 10).is_err());
+
+        let too_wide = Image {
+            width: 70_000,
+            height: 1,
+            pixels: vec![Rgb::BLACK; 70_000],
+        };
+        let mut frames = Frames::new();
+        frames.push(too_wide);
+        let mut out = vec![];
+        assert!(frames.write_gif(&mut out, 10).is_err());
+        assert!(out.is_empty());
+    }
+
+    #[test]
+    fn save_numbered() {
+        let directory = std::env::temp_dir().join(format!("aoc25-frames-{}", std::process::id()));
+        std::fs::create_dir_all(&directory).unwrap();
+        let mut frames = Frames::new();
+        frames.push(checkerboard());
+        frames.push(checkerboard());
+        frames.save_numbered(&directory, "frame").unwrap();
+        assert!(directory.join("frame0000.png").exists());
+        assert!(directory.join("frame0001.png").exists());
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+}
@@ -0,0 +1,232 @@
+//! k-d trees for nearest neighbour queries over integer points.
+#![allow(dead_code)]
+use super::vector::Vector;
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+/// A balanced k-d tree over a fixed set of points, identified by their index.
+///
+/// The tree is stored implicitly: each slice of `order` has its root in the middle, splitting
+/// along the axis given by the depth.
+#[derive(Debug, Clone)]
+pub struct KdTree<const N: usize> {
+    points: Vec<Vector<i64, N>>,
+    order: Vec<usize>,
+}
+
+impl<const N: usize> KdTree<N> {
+    pub fn new(points: &[Vector<i64, N>]) -> Self {
+        let mut order = (0..points.len()).collect::<Vec<_>>();
+        build(points, &mut order, 0);
+        KdTree {
+            points: points.to_vec(),
+            order,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.points.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.points.is_empty()
+    }
+
+    pub fn point(&self, index: usize) -> &Vector<i64, N> {
+        &self.points[index]
+    }
+
+    /// Return the `k` points nearest to `target` as `(squared distance, index)`, sorted by
+    /// distance then index.
+    pub fn nearest(&self, target: &Vector<i64, N>, k: usize) -> Vec<(i64, usize)> {
+        let mut best = BinaryHeap::with_capacity(k + 1);
+        if k > 0 {
+            self.search(target, k, 0..self.order.len(), 0, &mut best);
+        }
+        best.into_sorted_vec()
+    }
+
+    fn search(
+        &self,
+        target: &Vector<i64, N>,
+        k: usize,
+        range: std::ops::Range<usize>,
+        depth: usize,
+        best: &mut BinaryHeap<(i64, usize)>,
+    ) {
+        if range.is_empty() {
+            return;
+        }
+        let middle = range.start + range.len() / 2;
+        let index = self.order[middle];
+        let point = &self.points[index];
+        best.push((point.squared_distance(target), index));
+        if best.len() > k {
+            best.pop();
+        }
+
+        let axis = depth % N;
+        let diff = target[axis] - point[axis];
+        let (near, far) = if diff < 0 {
+            (range.start..middle, middle + 1..range.end)
+        } else {
+            (middle + 1..range.end, range.start..middle)
+        };
+        self.search(target, k, near, depth + 1, best);
+        // the far side may only hold closer points if the splitting plane is close enough.
+        if best.len() < k || diff * diff <= best.peek().unwrap().0 {
+            self.search(target, k, far, depth + 1, best);
+        }
+    }
+
+    /// Iterate over all the pairs of distinct points `(i, j, squared distance)` with `i < j`,
+    /// lazily, by increasing distance then `i` then `j`.
+    pub fn nearest_pairs(&self) -> NearestPairs<'_, N> {
+        let mut pairs = NearestPairs {
+            tree: self,
+            neighbours: vec![vec![]; self.len()],
+            cursors: vec![0; self.len()],
+            queue: BinaryHeap::new(),
+        };
+        for owner in 0..self.len() {
+            pairs.push_next(owner);
+        }
+        pairs
+    }
+}
+
+/// Sort the points of `order` so that the middle is the median along the axis of `depth`, then
+/// recurse on both halves.
+fn build<const N: usize>(points: &[Vector<i64, N>], order: &mut [usize], depth: usize) {
+    if order.len() <= 1 {
+        return;
+    }
+    let axis = depth % N;
+    let middle = order.len() / 2;
+    order.select_nth_unstable_by_key(middle, |i| points[*i][axis]);
+    let (left, right) = order.split_at_mut(middle);
+    build(points, left, depth + 1);
+    build(points, &mut right[1..], depth + 1);
+}
+
+/// Lazy stream of the pairs of points by increasing distance, see `KdTree::nearest_pairs`.
+///
+/// Each point walks through its neighbours, fetched from the tree in batches of doubling size,
+/// and the queue holds the next neighbour of every point.
+pub struct NearestPairs<'a, const N: usize> {
+    tree: &'a KdTree<N>,
+    /// The neighbours of each point fetched so far, nearest first.
+    neighbours: Vec<Vec<(i64, usize)>>,
+    /// Index of the next neighbour of each point.
+    cursors: Vec<usize>,
+    /// `(distance, i, j, owner)` of the next neighbour of each point, `i < j`.
+    queue: BinaryHeap<Reverse<(i64, usize, usize, usize)>>,
+}
+
+impl<const N: usize> NearestPairs<'_, N> {
+    /// Queue the next neighbour of `owner`, if any.
+    fn push_next(&mut self, owner: usize) {
+        let cursor = self.cursors[owner];
+        let fetched = self.neighbours[owner].len();
+        if cursor == fetched && fetched < self.tree.len() - 1 {
+            // the point itself is among the nearest, skip it.
+            let k = (2 * fetched).max(8).min(self.tree.len() - 1);
+            self.neighbours[owner] = self
+                .tree
+                .nearest(self.tree.point(owner), k + 1)
+                .into_iter()
+                .filter(|(_, j)| *j != owner)
+                .take(k)
+                .collect();
+        }
+        if let Some((distance, j)) = self.neighbours[owner].get(cursor) {
+            let (i, j) = (owner.min(*j), owner.max(*j));
+            self.queue.push(Reverse((*distance, i, j, owner)));
+        }
+    }
+}
+
+impl<const N: usize> Iterator for NearestPairs<'_, N> {
+    type Item = (usize, usize, i64);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let Reverse((distance, i, j, owner)) = self.queue.pop()?;
+            self.cursors[owner] += 1;
+            self.push_next(owner);
+            // each pair is met from both points, only keep it once.
+            if owner == i {
+                return Some((i, j, distance));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::KdTree;
+    use crate::etc::random::XorShift;
+    use crate::etc::vector::{Vec2, Vec3, Vector};
+
+    fn random_points(rng: &mut XorShift, n: usize) -> Vec<Vec3> {
+        (0..n)
+            .map(|_| Vector([rng.range(0..=20), rng.range(0..=20), rng.range(0..=20)]))
+            .collect()
+    }
+
+    #[test]
+    fn nearest() {
+        let points: Vec<Vec2> = vec![
+            Vector([0, 0]),
+            Vector([10, 10]),
+            Vector([3, 4]),
+            Vector([-1, 1]),
+            Vector([3, 4]),
+        ];
+        let tree = KdTree::new(&points);
+        assert_eq!(tree.nearest(&Vector([0, 0]), 2), vec![(0, 0), (2, 3)]);
+        assert_eq!(
+            tree.nearest(&Vector([3, 3]), 3),
+            vec![(1, 2), (1, 4), (18, 0)]
+        );
+        assert_eq!(tree.nearest(&Vector([0, 0]), 0), vec![]);
+        assert_eq!(tree.nearest(&Vector([0, 0]), 10).len(), 5);
+    }
+
+    #[test]
+    fn nearest_matches_brute_force() {
+        let mut rng = XorShift::new(11);
+        for _ in 0..50 {
+            let n = rng.range(1..=60) as usize;
+            let points = random_points(&mut rng, n);
+            let tree = KdTree::new(&points);
+            let target = random_points(&mut rng, 1)[0];
+            let k = rng.range(0..=10) as usize;
+            let mut expected = points
+                .iter()
+                .enumerate()
+                .map(|(i, p)| (p.squared_distance(&target), i))
+                .collect::<Vec<_>>();
+            expected.sort();
+            expected.truncate(k);
+            assert_eq!(tree.nearest(&target, k), expected);
+        }
+    }
+
+    #[test]
+    fn pairs_match_sorting() {
+        let mut rng = XorShift::new(13);
+        for n in [0, 1, 2, 9, 40, 100] {
+            let points = random_points(&mut rng, n);
+            let mut expected = vec![];
+            for i in 0..n {
+                for j in i + 1..n {
+                    expected.push((i, j, points[i].squared_distance(&points[j])));
+                }
+            }
+            expected.sort_by_key(|(_, _, d)| *d);
+            let tree = KdTree::new(&points);
+            assert_eq!(tree.nearest_pairs().collect::<Vec<_>>(), expected);
+        }
+    }
+}
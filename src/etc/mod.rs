@@ -1,2 +1,20 @@
 pub mod solution;
 pub mod grid;
+pub mod sparse_grid;
+pub mod bit_grid;
+pub mod automaton;
+pub mod image;
+pub mod vector;
+pub mod direction;
+pub mod geometry;
+pub mod compress;
+pub mod prefix_sum;
+pub mod search;
+pub mod hex;
+pub mod grid3;
+pub mod random;
+pub mod ranges;
+pub mod expr;
+pub mod beam;
+pub mod kdtree;
+pub mod graph;
@@ -0,0 +1,191 @@
+//! Prefix sums over slices and summed-area tables over grids.
+#![allow(dead_code)]
+use super::grid::{Grid, Point};
+use num::Num;
+use std::ops::{Bound, RangeBounds};
+
+/// Prefix sums of a slice, answering range sum queries in constant time.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PrefixSums<T> {
+    /// `sums[i]` is the sum of the first `i` values.
+    sums: Vec<T>,
+}
+
+impl<T> PrefixSums<T>
+where
+    T: Num + Copy,
+{
+    pub fn new(values: &[T]) -> Self {
+        let mut sums = Vec::with_capacity(values.len() + 1);
+        sums.push(T::zero());
+        for (i, value) in values.iter().enumerate() {
+            sums.push(sums[i] + *value);
+        }
+        PrefixSums { sums }
+    }
+
+    /// Return the number of values.
+    pub fn len(&self) -> usize {
+        self.sums.len() - 1
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Return the sum of the values in the given range of indices.
+    ///
+    /// Panics if the range goes past the end of the slice.
+    pub fn sum<R: RangeBounds<usize>>(&self, range: R) -> T {
+        let start = match range.start_bound() {
+            Bound::Included(start) => *start,
+            Bound::Excluded(start) => start + 1,
+            Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(end) => end + 1,
+            Bound::Excluded(end) => *end,
+            Bound::Unbounded => self.len(),
+        };
+        if start >= end {
+            return T::zero();
+        }
+        self.sums[end] - self.sums[start]
+    }
+}
+
+/// Summed-area table of a grid, answering rectangle sum queries in constant time.
+#[derive(Debug, Clone)]
+pub struct SummedAreaTable<T> {
+    /// The value at `(l, c)` is the sum of the cells above line `l` and left of column `c`.
+    sums: Grid<T>,
+}
+
+impl<T> SummedAreaTable<T>
+where
+    T: Num + Copy,
+{
+    /// Return the sum of the cells of the rectangle between the given corners, both included.
+    ///
+    /// Panics if a corner lies outside the grid.
+    pub fn sum(&self, top_left: &Point, bottom_right: &Point) -> T {
+        assert!(
+            self.sums.valid_position(&(*bottom_right + Point(1, 1)))
+                && top_left.0 >= 0
+                && top_left.1 >= 0,
+            "corners must lie inside the grid"
+        );
+        if top_left.0 > bottom_right.0 || top_left.1 > bottom_right.1 {
+            return T::zero();
+        }
+        let (l1, c1) = (top_left.0 as usize, top_left.1 as usize);
+        let (l2, c2) = (bottom_right.0 as usize + 1, bottom_right.1 as usize + 1);
+        let at = |line, column| *self.sums.at(line, column).unwrap();
+        // grouped so that unsigned types never go below zero.
+        (at(l2, c2) + at(l1, c1)) - (at(l1, c2) + at(l2, c1))
+    }
+
+    /// Return the sum of the whole grid.
+    pub fn total(&self) -> T {
+        *self.sums.items.last().unwrap()
+    }
+}
+
+impl<T> Grid<T>
+where
+    T: Num + Copy,
+{
+    /// Build the summed-area table of the grid.
+    pub fn prefix_sums(&self) -> SummedAreaTable<T> {
+        let mut sums = Grid {
+            lines: self.lines + 1,
+            columns: self.columns + 1,
+            items: vec![T::zero(); (self.lines + 1) * (self.columns + 1)],
+        };
+        for line in 0..self.lines {
+            let mut row_sum = T::zero();
+            for column in 0..self.columns {
+                row_sum = row_sum + *self.at(line, column).unwrap();
+                let above = *sums.at(line, column + 1).unwrap();
+                sums.update(&Point(line as i64 + 1, column as i64 + 1), above + row_sum);
+            }
+        }
+        SummedAreaTable { sums }
+    }
+}
+
+impl<T> Grid<T> {
+    /// Build the summed-area table counting the cells matching the predicate.
+    pub fn prefix_counts<P>(&self, predicate: P) -> SummedAreaTable<usize>
+    where
+        P: Fn(&T) -> bool,
+    {
+        let counts = Grid {
+            lines: self.lines,
+            columns: self.columns,
+            items: self
+                .items
+                .iter()
+                .map(|cell| usize::from(predicate(cell)))
+                .collect(),
+        };
+        counts.prefix_sums()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PrefixSums;
+    use crate::etc::grid::{Grid, Point};
+
+    #[test]
+    fn slices() {
+        let sums = PrefixSums::new(&[3u32, 1, 4, 1, 5, 9, 2, 6]);
+        assert_eq!(sums.len(), 8);
+        assert_eq!(sums.sum(..), 31);
+        assert_eq!(sums.sum(2..5), 10);
+        assert_eq!(sums.sum(2..=5), 19);
+        assert_eq!(sums.sum(6..), 8);
+        assert_eq!(sums.sum(3..3), 0);
+
+        let sums = PrefixSums::new(&[1.5, -2.0, 0.5]);
+        assert_eq!(sums.sum(..2), -0.5);
+    }
+
+    #[test]
+    fn grids() {
+        let grid = Grid {
+            lines: 3,
+            columns: 4,
+            items: vec![1u64, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12],
+        };
+        let table = grid.prefix_sums();
+        assert_eq!(table.total(), 78);
+        assert_eq!(table.sum(&Point(0, 0), &Point(0, 0)), 1);
+        assert_eq!(table.sum(&Point(1, 1), &Point(2, 2)), 6 + 7 + 10 + 11);
+        assert_eq!(table.sum(&Point(2, 0), &Point(2, 3)), 42);
+        assert_eq!(table.sum(&Point(0, 3), &Point(2, 3)), 24);
+        assert_eq!(table.sum(&Point(1, 2), &Point(0, 2)), 0);
+
+        let grid = Grid::new(
+            "
+            #..#
+            .##.
+            ####",
+        );
+        let table = grid.prefix_counts(|c| *c == '#');
+        assert_eq!(table.total(), 8);
+        assert_eq!(table.sum(&Point(0, 1), &Point(1, 2)), 2);
+    }
+
+    #[test]
+    #[should_panic]
+    fn out_of_grid() {
+        let grid = Grid {
+            lines: 2,
+            columns: 2,
+            items: vec![1, 2, 3, 4],
+        };
+        grid.prefix_sums().sum(&Point(0, 0), &Point(2, 1));
+    }
+}
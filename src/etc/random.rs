@@ -0,0 +1,67 @@
+//! Small deterministic pseudo-random generator, for randomized tests and simulations.
+#![allow(dead_code)]
+use std::ops::RangeInclusive;
+
+/// The xorshift64* generator: fast, seedable and good enough for tests, not for cryptography.
+#[derive(Debug, Clone)]
+pub struct XorShift {
+    state: u64,
+}
+
+impl XorShift {
+    pub fn new(seed: u64) -> Self {
+        // the state must never be zero.
+        XorShift {
+            state: seed ^ 0x9e37_79b9_7f4a_7c15,
+        }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state ^= self.state >> 12;
+        self.state ^= self.state << 25;
+        self.state ^= self.state >> 27;
+        self.state.wrapping_mul(0x2545_f491_4f6c_dd1d)
+    }
+
+    /// Return a number in the given range, with a negligible bias for small ranges.
+    pub fn range(&mut self, range: RangeInclusive<i64>) -> i64 {
+        let (start, end) = range.into_inner();
+        assert!(start <= end, "range must not be empty");
+        let width = (end as i128 - start as i128 + 1) as u128;
+        (start as i128 + (self.next_u64() as u128 % width) as i128) as i64
+    }
+
+    /// Return `true` with probability `1 / n`.
+    pub fn one_in(&mut self, n: u64) -> bool {
+        self.next_u64().is_multiple_of(n)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::XorShift;
+
+    #[test]
+    fn deterministic() {
+        let mut a = XorShift::new(42);
+        let mut b = XorShift::new(42);
+        let mut c = XorShift::new(43);
+        let xs = (0..10).map(|_| a.next_u64()).collect::<Vec<_>>();
+        assert_eq!(xs, (0..10).map(|_| b.next_u64()).collect::<Vec<_>>());
+        assert_ne!(xs, (0..10).map(|_| c.next_u64()).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn ranges() {
+        let mut rng = XorShift::new(0);
+        let mut seen = [false; 7];
+        for _ in 0..1000 {
+            let n = rng.range(-3..=3);
+            assert!((-3..=3).contains(&n));
+            seen[(n + 3) as usize] = true;
+        }
+        assert!(seen.iter().all(|s| *s));
+        assert_eq!(rng.range(i64::MIN..=i64::MIN), i64::MIN);
+        rng.range(i64::MIN..=i64::MAX);
+    }
+}
@@ -0,0 +1,400 @@
+//! Sets of integers stored as sorted disjoint ranges.
+#![allow(dead_code)]
+use num::PrimInt;
+use std::ops::RangeInclusive;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseRangeError {
+    /// The text has no `-` between the bounds.
+    MissingSeparator(String),
+    /// A bound could not be parsed.
+    Bound(String),
+}
+
+/// Parse a range written `lo-hi`, both bounds included. Bounds may be negative, like `-5--2`.
+pub fn parse_range<T: FromStr>(s: &str) -> Result<RangeInclusive<T>, ParseRangeError> {
+    let s = s.trim();
+    // the separator is the first `-` which is not the sign of the lower bound.
+    let separator = s
+        .char_indices()
+        .skip(1)
+        .find(|(_, c)| *c == '-')
+        .map(|(i, _)| i)
+        .ok_or_else(|| ParseRangeError::MissingSeparator(s.to_string()))?;
+    let parse = |bound: &str| {
+        bound
+            .trim()
+            .parse()
+            .map_err(|_| ParseRangeError::Bound(bound.to_string()))
+    };
+    Ok(parse(&s[..separator])?..=parse(&s[separator + 1..])?)
+}
+
+/// Return the number of values in the range.
+fn range_len<T: PrimInt>(range: &RangeInclusive<T>) -> u128 {
+    (range.end().to_i128().unwrap() - range.start().to_i128().unwrap() + 1) as u128
+}
+
+/// A set of integers, stored as sorted, disjoint and non-adjacent ranges.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RangeSet<T> {
+    ranges: Vec<RangeInclusive<T>>,
+}
+
+impl<T> Default for RangeSet<T> {
+    fn default() -> Self {
+        RangeSet { ranges: vec![] }
+    }
+}
+
+impl<T> RangeSet<T>
+where
+    T: PrimInt,
+{
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Return the ranges of the set, sorted in increasing order.
+    pub fn ranges(&self) -> &[RangeInclusive<T>] {
+        &self.ranges
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    /// Return the number of values in the set.
+    pub fn covered_len(&self) -> u128 {
+        self.ranges.iter().map(range_len).sum()
+    }
+
+    pub fn contains(&self, value: &T) -> bool {
+        let index = self.ranges.partition_point(|r| r.end() < value);
+        self.ranges.get(index).is_some_and(|r| r.start() <= value)
+    }
+
+    /// Add the values of the range to the set.
+    pub fn insert(&mut self, range: RangeInclusive<T>) {
+        if range.is_empty() {
+            return;
+        }
+        let (mut start, mut end) = range.into_inner();
+        // the ranges overlapping or adjacent to the new one are merged with it.
+        let first = self
+            .ranges
+            .partition_point(|r| r.end().saturating_add(T::one()) < start);
+        let last = self
+            .ranges
+            .partition_point(|r| *r.start() <= end.saturating_add(T::one()));
+        if first < last {
+            start = start.min(*self.ranges[first].start());
+            end = end.max(*self.ranges[last - 1].end());
+        }
+        self.ranges.splice(first..last, [start..=end]);
+    }
+
+    /// Remove the values of the range from the set.
+    pub fn remove(&mut self, range: RangeInclusive<T>) {
+        if range.is_empty() {
+            return;
+        }
+        let (start, end) = range.into_inner();
+        let first = self.ranges.partition_point(|r| *r.end() < start);
+        let last = self.ranges.partition_point(|r| *r.start() <= end);
+        if first == last {
+            return;
+        }
+        let mut pieces = vec![];
+        if *self.ranges[first].start() < start {
+            pieces.push(*self.ranges[first].start()..=start - T::one());
+        }
+        if *self.ranges[last - 1].end() > end {
+            pieces.push(end + T::one()..=*self.ranges[last - 1].end());
+        }
+        self.ranges.splice(first..last, pieces);
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        let mut union = self.clone();
+        for range in &other.ranges {
+            union.insert(range.clone());
+        }
+        union
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        let mut intersection = RangeSet::new();
+        let (mut i, mut j) = (0, 0);
+        while i < self.ranges.len() && j < other.ranges.len() {
+            let (a, b) = (&self.ranges[i], &other.ranges[j]);
+            let start = *a.start().max(b.start());
+            let end = *a.end().min(b.end());
+            if start <= end {
+                intersection.ranges.push(start..=end);
+            }
+            if a.end() < b.end() {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+        intersection
+    }
+
+    pub fn difference(&self, other: &Self) -> Self {
+        let mut difference = self.clone();
+        for range in &other.ranges {
+            difference.remove(range.clone());
+        }
+        difference
+    }
+
+    /// Return the values of `bounds` which are not in the set.
+    pub fn complement(&self, bounds: RangeInclusive<T>) -> Self {
+        RangeSet::from(bounds).difference(self)
+    }
+}
+
+impl<T: PrimInt> From<RangeInclusive<T>> for RangeSet<T> {
+    fn from(range: RangeInclusive<T>) -> Self {
+        let mut set = RangeSet::new();
+        set.insert(range);
+        set
+    }
+}
+
+impl<T: PrimInt> FromIterator<RangeInclusive<T>> for RangeSet<T> {
+    fn from_iter<I: IntoIterator<Item = RangeInclusive<T>>>(iter: I) -> Self {
+        let mut set = RangeSet::new();
+        for range in iter {
+            set.insert(range);
+        }
+        set
+    }
+}
+
+/// A mapping of values shifting disjoint source ranges to destinations, the values outside of
+/// the source ranges being mapped to themselves.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RangeMap<T> {
+    /// `(source, destination start)` pairs, sorted by source.
+    entries: Vec<(RangeInclusive<T>, T)>,
+}
+
+impl<T> Default for RangeMap<T> {
+    fn default() -> Self {
+        RangeMap { entries: vec![] }
+    }
+}
+
+impl<T> RangeMap<T>
+where
+    T: PrimInt,
+{
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Map the values of `source` to the values starting at `destination`.
+    ///
+    /// Panics if `source` overlaps the source of another entry.
+    pub fn insert(&mut self, source: RangeInclusive<T>, destination: T) {
+        let index = self
+            .entries
+            .partition_point(|(s, _)| s.start() < source.start());
+        let overlaps = |other: Option<&(RangeInclusive<T>, T)>| {
+            other.is_some_and(|(s, _)| s.start() <= source.end() && source.start() <= s.end())
+        };
+        assert!(
+            !overlaps(self.entries.get(index))
+                && !overlaps(index.checked_sub(1).and_then(|i| self.entries.get(i))),
+            "source ranges must not overlap"
+        );
+        self.entries.insert(index, (source, destination));
+    }
+
+    pub fn map(&self, value: T) -> T {
+        let index = self.entries.partition_point(|(s, _)| *s.end() < value);
+        match self.entries.get(index) {
+            Some((source, destination)) if *source.start() <= value => {
+                *destination + (value - *source.start())
+            }
+            _ => value,
+        }
+    }
+
+    /// Map all the values of the set, splitting its ranges along the source ranges.
+    pub fn map_set(&self, set: &RangeSet<T>) -> RangeSet<T> {
+        let mut mapped = RangeSet::new();
+        for range in set.ranges() {
+            let mut unmapped = RangeSet::from(range.clone());
+            for (source, destination) in &self.entries {
+                let start = *range.start().max(source.start());
+                let end = *range.end().min(source.end());
+                if start <= end {
+                    let offset = |v: T| *destination + (v - *source.start());
+                    mapped.insert(offset(start)..=offset(end));
+                    unmapped.remove(start..=end);
+                }
+            }
+            mapped = mapped.union(&unmapped);
+        }
+        mapped
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ParseRangeError, RangeMap, RangeSet, parse_range};
+    use crate::etc::random::XorShift;
+    use std::collections::BTreeSet;
+    use std::ops::RangeInclusive;
+
+    #[test]
+    fn parsing() {
+        assert_eq!(parse_range::<u64>("11-22"), Ok(11..=22));
+        assert_eq!(parse_range::<i32>(" -5--2 "), Ok(-5..=-2));
+        assert_eq!(
+            parse_range::<u8>("12"),
+            Err(ParseRangeError::MissingSeparator("12".to_string()))
+        );
+        assert_eq!(
+            parse_range::<u8>("1-x"),
+            Err(ParseRangeError::Bound("x".to_string()))
+        );
+    }
+
+    #[test]
+    fn merging() {
+        let set: RangeSet<u64> = [3..=5, 10..=14, 16..=20, 12..=18, 6..=6]
+            .into_iter()
+            .collect();
+        assert_eq!(set.ranges(), &[3..=6, 10..=20]);
+        assert_eq!(set.covered_len(), 15);
+        assert!(set.contains(&6));
+        assert!(!set.contains(&7));
+        assert_eq!(set.complement(0..=25).ranges(), &[0..=2, 7..=9, 21..=25]);
+
+        // bounds of the type
+        let mut set = RangeSet::from(u8::MIN..=u8::MAX);
+        assert_eq!(set.covered_len(), 256);
+        set.remove(0..=0);
+        set.remove(255..=255);
+        assert_eq!(set.ranges(), &[1..=254]);
+        set.insert(255..=255);
+        assert_eq!(set.ranges(), &[1..=255]);
+    }
+
+    #[test]
+    fn mapping() {
+        let mut map = RangeMap::new();
+        map.insert(98..=99, 50);
+        map.insert(50..=97, 52);
+        assert_eq!(map.map(79), 81);
+        assert_eq!(map.map(14), 14);
+        assert_eq!(map.map(99), 51);
+        let seeds: RangeSet<u64> = [79..=92, 55..=67].into_iter().collect();
+        assert_eq!(map.map_set(&seeds).ranges(), &[57..=69, 81..=94]);
+        let split = map.map_set(&RangeSet::from(40..=99));
+        assert_eq!(split.ranges(), &[40..=99]);
+        assert_eq!(
+            map.map_set(&RangeSet::from(96..=100)).ranges(),
+            &[50..=51, 98..=100]
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn overlapping_sources() {
+        let mut map = RangeMap::new();
+        map.insert(10..=20, 0u32);
+        map.insert(20..=30, 100);
+    }
+
+    fn random_range(rng: &mut XorShift) -> RangeInclusive<i32> {
+        let start = rng.range(-2..=40) as i32;
+        start..=start + rng.range(-1..=10) as i32
+    }
+
+    fn random_set(rng: &mut XorShift) -> (RangeSet<i32>, BTreeSet<i32>) {
+        let mut set = RangeSet::new();
+        let mut model = BTreeSet::new();
+        for _ in 0..rng.range(0..=6) {
+            let range = random_range(rng);
+            if rng.one_in(3) {
+                set.remove(range.clone());
+                range.for_each(|v| {
+                    model.remove(&v);
+                });
+            } else {
+                set.insert(range.clone());
+                model.extend(range);
+            }
+        }
+        (set, model)
+    }
+
+    fn to_model(set: &RangeSet<i32>) -> BTreeSet<i32> {
+        set.ranges().iter().cloned().flatten().collect()
+    }
+
+    fn is_canonical(set: &RangeSet<i32>) -> bool {
+        set.ranges()
+            .windows(2)
+            .all(|w| w[0].end() + 1 < *w[1].start())
+            && set.ranges().iter().all(|r| !r.is_empty())
+    }
+
+    #[test]
+    fn matches_model() {
+        let mut rng = XorShift::new(5);
+        for _ in 0..500 {
+            let (a, model_a) = random_set(&mut rng);
+            let (b, model_b) = random_set(&mut rng);
+            assert!(is_canonical(&a));
+            assert_eq!(to_model(&a), model_a);
+            assert_eq!(a.covered_len(), model_a.len() as u128);
+            for v in -5..55 {
+                assert_eq!(a.contains(&v), model_a.contains(&v));
+            }
+
+            let union = a.union(&b);
+            let intersection = a.intersection(&b);
+            let difference = a.difference(&b);
+            let complement = a.complement(0..=30);
+            for set in [&union, &intersection, &difference, &complement] {
+                assert!(is_canonical(set));
+            }
+            assert_eq!(to_model(&union), &model_a | &model_b);
+            assert_eq!(to_model(&intersection), &model_a & &model_b);
+            assert_eq!(to_model(&difference), &model_a - &model_b);
+            assert_eq!(
+                to_model(&complement),
+                &(0..=30).collect::<BTreeSet<_>>() - &model_a
+            );
+        }
+    }
+
+    #[test]
+    fn mapping_matches_model() {
+        let mut rng = XorShift::new(7);
+        for _ in 0..200 {
+            let mut map = RangeMap::new();
+            let mut next = rng.range(-2..=5) as i32;
+            for _ in 0..rng.range(0..=4) {
+                let len = rng.range(0..=8) as i32;
+                map.insert(next..=next + len, rng.range(-20..=60) as i32);
+                next += len + 1 + rng.range(0..=3) as i32;
+            }
+            let (set, model) = random_set(&mut rng);
+            let mapped = map.map_set(&set);
+            assert!(is_canonical(&mapped));
+            assert_eq!(
+                to_model(&mapped),
+                model.iter().map(|v| map.map(*v)).collect::<BTreeSet<_>>()
+            );
+        }
+    }
+}
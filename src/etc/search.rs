@@ -0,0 +1,144 @@
+//! Breadth-first search and flood fill over any kind of lattice.
+#![allow(dead_code)]
+use super::grid::{Grid, Point};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::hash::Hash;
+
+/// Return the distance from `start` to every reachable node, `neighbours` giving the nodes one
+/// step away from a node.
+pub fn bfs_distances<N, F, I>(start: N, mut neighbours: F) -> HashMap<N, usize>
+where
+    N: Clone + Eq + Hash,
+    F: FnMut(&N) -> I,
+    I: IntoIterator<Item = N>,
+{
+    let mut distances = HashMap::from([(start.clone(), 0)]);
+    let mut queue = VecDeque::from([start]);
+    while let Some(node) = queue.pop_front() {
+        let distance = distances[&node];
+        for next in neighbours(&node) {
+            if !distances.contains_key(&next) {
+                distances.insert(next.clone(), distance + 1);
+                queue.push_back(next);
+            }
+        }
+    }
+    distances
+}
+
+/// Return the length of a shortest path from `start` to a node satisfying `goal`.
+pub fn shortest_path_len<N, G, F, I>(start: N, goal: G, mut neighbours: F) -> Option<usize>
+where
+    N: Clone + Eq + Hash,
+    G: Fn(&N) -> bool,
+    F: FnMut(&N) -> I,
+    I: IntoIterator<Item = N>,
+{
+    let mut seen = HashSet::from([start.clone()]);
+    let mut queue = VecDeque::from([(start, 0)]);
+    while let Some((node, distance)) = queue.pop_front() {
+        if goal(&node) {
+            return Some(distance);
+        }
+        for next in neighbours(&node) {
+            if seen.insert(next.clone()) {
+                queue.push_back((next, distance + 1));
+            }
+        }
+    }
+    None
+}
+
+/// Return the set of nodes reachable from `start`, `start` included.
+pub fn flood_fill<N, F, I>(start: N, mut neighbours: F) -> HashSet<N>
+where
+    N: Clone + Eq + Hash,
+    F: FnMut(&N) -> I,
+    I: IntoIterator<Item = N>,
+{
+    let mut seen = HashSet::from([start.clone()]);
+    let mut stack = vec![start];
+    while let Some(node) = stack.pop() {
+        for next in neighbours(&node) {
+            if seen.insert(next.clone()) {
+                stack.push(next);
+            }
+        }
+    }
+    seen
+}
+
+impl<T> Grid<T> {
+    /// Return the positions of the square grid reachable from `start` with taxicab steps through
+    /// the cells satisfying `passable`. `start` itself is always included.
+    pub fn flood_fill<P>(&self, start: &Point, passable: P) -> HashSet<Point>
+    where
+        P: Fn(&T) -> bool,
+    {
+        flood_fill(*start, |pos| self.passable_neighbours(pos, &passable))
+    }
+
+    /// Return the taxicab distance from `start` to every position reachable through the cells
+    /// satisfying `passable`.
+    pub fn distances<P>(&self, start: &Point, passable: P) -> HashMap<Point, usize>
+    where
+        P: Fn(&T) -> bool,
+    {
+        bfs_distances(*start, |pos| self.passable_neighbours(pos, &passable))
+    }
+
+    fn passable_neighbours<P>(&self, origin: &Point, passable: &P) -> Vec<Point>
+    where
+        P: Fn(&T) -> bool,
+    {
+        let mut neighbours = Vec::with_capacity(4);
+        self.for_each_taxicab_neighbour(origin, |pos, cell| {
+            if passable(cell) {
+                neighbours.push(pos);
+            }
+        });
+        neighbours
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{bfs_distances, flood_fill, shortest_path_len};
+    use crate::etc::grid::{Grid, Point};
+
+    const MAZE: &str = "
+        ..#....
+        .##.##.
+        ...#...
+        ##.#.#.
+        ...#.#.";
+
+    #[test]
+    fn generic() {
+        // numbers reachable from 1 by doubling or adding 3, below 20.
+        let next = |n: &u32| [n * 2, n + 3].into_iter().filter(|n| *n < 20);
+        let reachable = flood_fill(1, next);
+        assert_eq!(reachable.len(), 13);
+        assert!(!reachable.contains(&3));
+        let distances = bfs_distances(1, next);
+        assert_eq!(distances[&1], 0);
+        assert_eq!(distances[&8], 2);
+        assert_eq!(distances[&19], 4);
+        assert_eq!(shortest_path_len(1, |n| *n == 13, next), Some(4));
+        assert_eq!(shortest_path_len(1, |n| *n == 3, next), None);
+    }
+
+    #[test]
+    fn grids() {
+        let maze = Grid::new(MAZE);
+        let open = maze.flood_fill(&Point(0, 0), |c| *c == '.');
+        assert_eq!(open.len(), 10);
+        assert!(open.contains(&Point(4, 2)));
+        assert!(!open.contains(&Point(0, 3)));
+
+        let distances = maze.distances(&Point(0, 3), |c| *c == '.');
+        assert_eq!(distances[&Point(4, 6)], 7);
+        assert_eq!(distances[&Point(4, 4)], 9);
+        assert_eq!(distances.len(), 13);
+    }
+}
@@ -0,0 +1,288 @@
+//! Sparse and unbounded 2D grid.
+#![allow(dead_code)]
+use super::grid::{ALL_DIRECTIONS, Grid, Point, TAXICAB_DIRECTIONS, for_each_neighbour};
+use std::collections::HashMap;
+
+/// An unbounded 2D grid where only the cells that differ from a default value are stored.
+///
+/// Inserting the default value clears the cell instead of storing it, so that `len()` and
+/// `bounds()` only account for the cells that differ.
+///
+/// Coordinates are expressed as `Point(line, column)` like in `Grid`, but they can be negative or
+/// arbitrarily large. The grid keeps track of the bounding box of its stored cells.
+#[derive(Clone)]
+pub struct SparseGrid<T> {
+    default: T,
+    cells: HashMap<Point, T>,
+    /// `(top-left, bottom-right)` corners of the stored cells.
+    bounds: Option<(Point, Point)>,
+}
+
+impl<T> SparseGrid<T> {
+    /// Create an empty grid where every cell holds `default`.
+    pub fn new(default: T) -> Self {
+        SparseGrid {
+            default,
+            cells: HashMap::new(),
+            bounds: None,
+        }
+    }
+
+    /// Return the number of stored cells.
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    /// Return the top-left and bottom-right corners of the bounding box of the stored cells.
+    pub fn bounds(&self) -> Option<(Point, Point)> {
+        self.bounds
+    }
+
+    /// Test whether a value is stored at the given point.
+    pub fn contains(&self, pos: &Point) -> bool {
+        self.cells.contains_key(pos)
+    }
+
+    /// Retrieve value at given point, the default value if nothing is stored there.
+    pub fn get(&self, pos: &Point) -> &T {
+        self.cells.get(pos).unwrap_or(&self.default)
+    }
+
+    /// Retrieve the stored value at the given point.
+    ///
+    /// The cell stays stored even if it is set to the default value, use `insert` to clear it.
+    pub fn get_mut(&mut self, pos: &Point) -> Option<&mut T> {
+        self.cells.get_mut(pos)
+    }
+
+    /// Remove the value stored at the given point, the cell then holds the default value again.
+    pub fn remove(&mut self, pos: &Point) -> Option<T> {
+        let old = self.cells.remove(pos);
+        if old.is_some()
+            && let Some((tl, br)) = self.bounds
+            && (pos.0 == tl.0 || pos.0 == br.0 || pos.1 == tl.1 || pos.1 == br.1)
+        {
+            // the bounding box may shrink
+            self.bounds = self.cells.keys().fold(None, |bounds, p| {
+                Some(match bounds {
+                    None => (*p, *p),
+                    Some((tl, br)) => (
+                        Point(tl.0.min(p.0), tl.1.min(p.1)),
+                        Point(br.0.max(p.0), br.1.max(p.1)),
+                    ),
+                })
+            });
+        }
+        old
+    }
+
+    /// Iterate over the stored cells, in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item = (&Point, &T)> {
+        self.cells.iter()
+    }
+
+    /// Search for a stored element, returning the first position in reading order.
+    pub fn position<P>(&self, predicate: P) -> Option<Point>
+    where
+        P: Fn(&T) -> bool,
+    {
+        self.cells
+            .iter()
+            .filter(|(_, val)| predicate(val))
+            .map(|(pos, _)| *pos)
+            .min()
+    }
+
+    /// Search for a stored element, returning the first one in reading order.
+    pub fn find<P>(&self, predicate: P) -> Option<&T>
+    where
+        P: Fn(&T) -> bool,
+    {
+        self.position(predicate).map(|pos| &self.cells[&pos])
+    }
+
+    pub fn for_each_with_position<F>(&self, mut f: F)
+    where
+        F: FnMut(Point, &T),
+    {
+        self.cells.iter().for_each(|(pos, item)| f(*pos, item));
+    }
+
+    pub fn for_each_taxicab_neighbour<F>(&self, origin: &Point, f: F)
+    where
+        F: FnMut(Point, &T),
+    {
+        for_each_neighbour(origin, &TAXICAB_DIRECTIONS, |pos| self.cell(pos), f);
+    }
+
+    pub fn for_each_tchebychev_neighbour<F>(&self, origin: &Point, f: F)
+    where
+        F: FnMut(Point, &T),
+    {
+        for_each_neighbour(origin, &ALL_DIRECTIONS, |pos| self.cell(pos), f);
+    }
+
+    fn cell(&self, pos: Point) -> Option<(Point, &T)> {
+        Some((pos, self.get(&pos)))
+    }
+}
+
+impl<T> SparseGrid<T>
+where
+    T: PartialEq,
+{
+    /// Store a value at the given point, returning the previously stored value.
+    ///
+    /// Storing the default value removes the cell.
+    pub fn insert(&mut self, pos: Point, val: T) -> Option<T> {
+        if val == self.default {
+            return self.remove(&pos);
+        }
+        self.bounds = Some(match self.bounds {
+            None => (pos, pos),
+            Some((tl, br)) => (
+                Point(tl.0.min(pos.0), tl.1.min(pos.1)),
+                Point(br.0.max(pos.0), br.1.max(pos.1)),
+            ),
+        });
+        self.cells.insert(pos, val)
+    }
+}
+
+impl<T> SparseGrid<T>
+where
+    T: Clone + PartialEq,
+{
+    /// Read a dense grid, storing only the cells that differ from `default`.
+    pub fn from_grid(grid: &Grid<T>, default: T) -> Self {
+        let mut sparse = SparseGrid::new(default);
+        grid.for_each_with_position(|pos, item| {
+            if *item != sparse.default {
+                sparse.insert(pos, item.clone());
+            }
+        });
+        sparse
+    }
+
+    /// Return a dense grid covering the bounding box of the stored cells.
+    ///
+    /// The top-left-most item of the dense grid corresponds to the top-left corner of `bounds()`.
+    /// Return `None` if no cell is stored.
+    pub fn to_grid(&self) -> Option<Grid<T>> {
+        let (tl, br) = self.bounds?;
+        let lines = (br.0 - tl.0 + 1) as usize;
+        let columns = (br.1 - tl.1 + 1) as usize;
+        let mut items = vec![self.default.clone(); lines * columns];
+        for (pos, item) in &self.cells {
+            let offset = *pos - tl;
+            items[offset.0 as usize * columns + offset.1 as usize] = item.clone();
+        }
+        Some(Grid {
+            lines,
+            columns,
+            items,
+        })
+    }
+}
+
+impl<T: std::fmt::Debug> std::fmt::Debug for SparseGrid<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some((tl, br)) = self.bounds {
+            for line in tl.0..=br.0 {
+                for column in tl.1..=br.1 {
+                    self.get(&Point(line, column)).fmt(f)?;
+                }
+                f.write_str("\n")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SparseGrid;
+    use crate::etc::grid::{Grid, Point};
+
+    #[test]
+    fn insert_and_bounds() {
+        let mut g = SparseGrid::new('.');
+        assert_eq!(g.bounds(), None);
+        g.insert(Point(-5, 20_000), '#');
+        g.insert(Point(3, -1), '#');
+        assert_eq!(g.bounds(), Some((Point(-5, -1), Point(3, 20_000))));
+        assert_eq!(g.get(&Point(3, -1)), &'#');
+        assert_eq!(g.get(&Point(0, 0)), &'.');
+        assert_eq!(g.len(), 2);
+    }
+
+    #[test]
+    fn default_values_are_not_stored() {
+        let mut g = SparseGrid::new('.');
+        assert_eq!(g.insert(Point(0, 0), '.'), None);
+        assert!(g.is_empty());
+        assert_eq!(g.bounds(), None);
+        g.insert(Point(0, 0), '#');
+        g.insert(Point(5, 5), '#');
+        assert_eq!(g.insert(Point(5, 5), '.'), Some('#'));
+        assert_eq!(g.len(), 1);
+        assert_eq!(g.bounds(), Some((Point(0, 0), Point(0, 0))));
+    }
+
+    #[test]
+    fn remove_shrinks_bounds() {
+        let mut g = SparseGrid::new(0);
+        g.insert(Point(0, 0), 1);
+        g.insert(Point(1, 1), 2);
+        g.insert(Point(10, 10), 3);
+        assert_eq!(g.remove(&Point(10, 10)), Some(3));
+        assert_eq!(g.bounds(), Some((Point(0, 0), Point(1, 1))));
+        assert_eq!(g.remove(&Point(10, 10)), None);
+        g.remove(&Point(0, 0));
+        g.remove(&Point(1, 1));
+        assert!(g.is_empty());
+        assert_eq!(g.bounds(), None);
+    }
+
+    #[test]
+    fn neighbours_are_unbounded() {
+        let mut g = SparseGrid::new(false);
+        g.insert(Point(-1, 0), true);
+        let mut count = 0;
+        let mut set = 0;
+        g.for_each_tchebychev_neighbour(&Point(0, 0), |_, v| {
+            count += 1;
+            if *v {
+                set += 1;
+            }
+        });
+        assert_eq!((count, set), (8, 1));
+    }
+
+    #[test]
+    fn search() {
+        let mut g = SparseGrid::new('.');
+        g.insert(Point(7, 2), 'a');
+        g.insert(Point(7, 1), 'a');
+        g.insert(Point(-3, 9), 'b');
+        assert_eq!(g.position(|v| *v == 'a'), Some(Point(7, 1)));
+        assert_eq!(g.find(|v| *v == 'b'), Some(&'b'));
+        assert_eq!(g.position(|v| *v == 'c'), None);
+    }
+
+    #[test]
+    fn dense_conversions() {
+        let dense = Grid::new("...\n.#.\n..#\n");
+        let sparse = SparseGrid::from_grid(&dense, '.');
+        assert_eq!(sparse.len(), 2);
+        assert_eq!(sparse.bounds(), Some((Point(1, 1), Point(2, 2))));
+        let back = sparse.to_grid().unwrap();
+        assert_eq!((back.lines, back.columns), (2, 2));
+        assert_eq!(back.items, vec!['#', '.', '.', '#']);
+        assert_eq!(format!("{sparse:?}"), "'#''.'\n'.''#'\n");
+    }
+}
@@ -0,0 +1,275 @@
+//! Vectors of any dimension over any numeric type.
+#![allow(dead_code)]
+use super::grid::Point;
+use num::Num;
+use std::ops::{Add, AddAssign, Index, IndexMut, Mul, Neg, Sub, SubAssign};
+use std::str::FromStr;
+
+/// A vector (or point) with `N` coordinates of type `T`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Vector<T, const N: usize>(pub [T; N]);
+
+pub type Vec2<T = i64> = Vector<T, 2>;
+pub type Vec3<T = i64> = Vector<T, 3>;
+pub type Vec4<T = i64> = Vector<T, 4>;
+
+/// Return `|a - b|`, also for unsigned types.
+fn abs_diff<T: Num + PartialOrd + Copy>(a: T, b: T) -> T {
+    if a > b { a - b } else { b - a }
+}
+
+impl<T, const N: usize> Vector<T, N>
+where
+    T: Num + Copy,
+{
+    /// The vector whose coordinates are all zero.
+    pub fn zero() -> Self {
+        Vector([T::zero(); N])
+    }
+
+    pub fn x(&self) -> T {
+        self.0[0]
+    }
+
+    pub fn y(&self) -> T {
+        self.0[1]
+    }
+
+    pub fn z(&self) -> T {
+        self.0[2]
+    }
+
+    pub fn w(&self) -> T {
+        self.0[3]
+    }
+
+    /// Apply `f` to each coordinate.
+    pub fn map<U, F: Fn(T) -> U>(&self, f: F) -> Vector<U, N> {
+        Vector(self.0.map(f))
+    }
+
+    /// Combine the coordinates of both vectors pairwise with `f`.
+    pub fn zip_with<F: Fn(T, T) -> T>(&self, other: &Self, f: F) -> Self {
+        Vector(std::array::from_fn(|i| f(self.0[i], other.0[i])))
+    }
+
+    pub fn dot(&self, other: &Self) -> T {
+        (0..N).fold(T::zero(), |acc, i| acc + self.0[i] * other.0[i])
+    }
+
+    /// Return the squared euclidean distance to the other point.
+    pub fn squared_distance(&self, other: &Self) -> T
+    where
+        T: PartialOrd,
+    {
+        let d = self.zip_with(other, abs_diff);
+        d.dot(&d)
+    }
+
+    /// Return the taxicab distance to the other point.
+    pub fn manhattan_distance(&self, other: &Self) -> T
+    where
+        T: PartialOrd,
+    {
+        (0..N).fold(T::zero(), |acc, i| acc + abs_diff(self.0[i], other.0[i]))
+    }
+
+    /// Return the greatest distance along any axis to the other point.
+    pub fn chebyshev_distance(&self, other: &Self) -> T
+    where
+        T: PartialOrd,
+    {
+        (0..N).fold(T::zero(), |acc, i| {
+            let d = abs_diff(self.0[i], other.0[i]);
+            if d > acc { d } else { acc }
+        })
+    }
+
+    /// Componentwise minimum.
+    pub fn componentwise_min(&self, other: &Self) -> Self
+    where
+        T: PartialOrd,
+    {
+        self.zip_with(other, |a, b| if b < a { b } else { a })
+    }
+
+    /// Componentwise maximum.
+    pub fn componentwise_max(&self, other: &Self) -> Self
+    where
+        T: PartialOrd,
+    {
+        self.zip_with(other, |a, b| if b > a { b } else { a })
+    }
+}
+
+impl<T, const N: usize> Index<usize> for Vector<T, N> {
+    type Output = T;
+
+    fn index(&self, index: usize) -> &T {
+        &self.0[index]
+    }
+}
+
+impl<T, const N: usize> IndexMut<usize> for Vector<T, N> {
+    fn index_mut(&mut self, index: usize) -> &mut T {
+        &mut self.0[index]
+    }
+}
+
+impl<T: Num + Copy, const N: usize> Add for Vector<T, N> {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        self.zip_with(&other, |a, b| a + b)
+    }
+}
+
+impl<T: Num + Copy, const N: usize> Sub for Vector<T, N> {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        self.zip_with(&other, |a, b| a - b)
+    }
+}
+
+impl<T: Num + Copy, const N: usize> AddAssign for Vector<T, N> {
+    fn add_assign(&mut self, other: Self) {
+        *self = *self + other;
+    }
+}
+
+impl<T: Num + Copy, const N: usize> SubAssign for Vector<T, N> {
+    fn sub_assign(&mut self, other: Self) {
+        *self = *self - other;
+    }
+}
+
+impl<T: Num + Copy + Neg<Output = T>, const N: usize> Neg for Vector<T, N> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        self.map(|a| -a)
+    }
+}
+
+/// Multiplication by a scalar.
+impl<T: Num + Copy, const N: usize> Mul<T> for Vector<T, N> {
+    type Output = Self;
+
+    fn mul(self, rhs: T) -> Self {
+        self.map(|a| a * rhs)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseVectorError {
+    /// The text does not have the expected number of coordinates.
+    Dimension { expected: usize, found: usize },
+    /// A coordinate could not be parsed.
+    Coordinate(String),
+}
+
+/// Parse comma separated coordinates like `"1,-2,3"`, whitespace around coordinates is ignored.
+impl<T, const N: usize> FromStr for Vector<T, N>
+where
+    T: FromStr + Copy + Default,
+{
+    type Err = ParseVectorError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parts = s.split(',').map(|part| part.trim()).collect::<Vec<_>>();
+        if parts.len() != N {
+            return Err(ParseVectorError::Dimension {
+                expected: N,
+                found: parts.len(),
+            });
+        }
+        let mut coordinates = [T::default(); N];
+        for (coordinate, part) in coordinates.iter_mut().zip(parts) {
+            *coordinate = part
+                .parse()
+                .map_err(|_| ParseVectorError::Coordinate(part.to_string()))?;
+        }
+        Ok(Vector(coordinates))
+    }
+}
+
+impl From<Point> for Vec2<i64> {
+    fn from(value: Point) -> Self {
+        Vector([value.0, value.1])
+    }
+}
+
+impl From<Vec2<i64>> for Point {
+    fn from(value: Vec2<i64>) -> Self {
+        Point(value.0[0], value.0[1])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ParseVectorError, Vec2, Vec3, Vec4, Vector};
+    use crate::etc::grid::Point;
+
+    #[test]
+    fn arithmetic() {
+        let a = Vector([1, 2, 3]);
+        let b = Vector([4, -5, 6]);
+        assert_eq!(a + b, Vector([5, -3, 9]));
+        assert_eq!(a - b, Vector([-3, 7, -3]));
+        assert_eq!(-a, Vector([-1, -2, -3]));
+        assert_eq!(a * 2, Vector([2, 4, 6]));
+        assert_eq!(a.dot(&b), 12);
+        let mut c = a;
+        c += b;
+        c -= a;
+        assert_eq!(c, b);
+    }
+
+    #[test]
+    fn distances() {
+        let a: Vec3 = Vector([162, 817, 812]);
+        let b: Vec3 = Vector([425, 690, 689]);
+        assert_eq!(a.squared_distance(&b), 263 * 263 + 127 * 127 + 123 * 123);
+        assert_eq!(a.manhattan_distance(&b), 263 + 127 + 123);
+        assert_eq!(a.chebyshev_distance(&b), 263);
+
+        // unsigned coordinates
+        let a: Vec2<u32> = Vector([1, 10]);
+        let b: Vec2<u32> = Vector([4, 6]);
+        assert_eq!(a.manhattan_distance(&b), 7);
+        assert_eq!(a.squared_distance(&b), 25);
+    }
+
+    #[test]
+    fn min_max() {
+        let a: Vec4 = Vector([1, 5, -2, 0]);
+        let b: Vec4 = Vector([3, 4, -1, 0]);
+        assert_eq!(a.componentwise_min(&b), Vector([1, 4, -2, 0]));
+        assert_eq!(a.componentwise_max(&b), Vector([3, 5, -1, 0]));
+    }
+
+    #[test]
+    fn parse() {
+        assert_eq!("162,817,812".parse::<Vec3>(), Ok(Vector([162, 817, 812])));
+        assert_eq!(" 7, -1 ".parse::<Vec2>(), Ok(Vector([7, -1])));
+        assert_eq!(
+            "1,2".parse::<Vec3>(),
+            Err(ParseVectorError::Dimension {
+                expected: 3,
+                found: 2
+            })
+        );
+        assert_eq!(
+            "1,x".parse::<Vec2>(),
+            Err(ParseVectorError::Coordinate("x".to_string()))
+        );
+    }
+
+    #[test]
+    fn point_conversion() {
+        let v: Vec2 = Point(3, -4).into();
+        assert_eq!(v, Vector([3, -4]));
+        assert_eq!(Point::from(v), Point(3, -4));
+    }
+}